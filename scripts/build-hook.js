@@ -0,0 +1,28 @@
+// Builds the tusker-hook helper and copies it to where Tauri expects the
+// sidecar declared in `bundle.externalBin`: src-tauri/binaries/tusker-hook-<target triple>
+import { execFileSync } from "node:child_process";
+import { copyFileSync, mkdirSync } from "node:fs";
+import { dirname, join } from "node:path";
+import { fileURLToPath } from "node:url";
+
+const root = join(dirname(fileURLToPath(import.meta.url)), "..", "src-tauri");
+
+// Set by the Tauri CLI when building for another target
+const target = process.env.TAURI_ENV_TARGET_TRIPLE;
+const triple =
+  target ??
+  execFileSync("rustc", ["-vV"], { encoding: "utf8" })
+    .split("\n")
+    .find((line) => line.startsWith("host:"))
+    .slice("host:".length)
+    .trim();
+
+const args = ["build", "--release", "-p", "tusker-hook"];
+if (target) args.push("--target", target);
+execFileSync("cargo", args, { cwd: root, stdio: "inherit" });
+
+const ext = triple.includes("windows") ? ".exe" : "";
+const built = join(root, "target", target ?? "", "release", `tusker-hook${ext}`);
+
+mkdirSync(join(root, "binaries"), { recursive: true });
+copyFileSync(built, join(root, "binaries", `tusker-hook-${triple}${ext}`));
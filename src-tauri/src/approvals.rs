@@ -0,0 +1,315 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::hooks::HookEvent;
+use crate::models::Config;
+use crate::notifications;
+
+pub const APPROVAL_DECISIONS: [&str; 3] = ["allow", "deny", "ask"];
+
+/// Longest approval timeout, kept below the timeout Claude Code gives the hook
+pub const MAX_APPROVAL_TIMEOUT_SECS: u32 = 540;
+
+/// A tool call waiting for the user to allow or deny it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingApproval {
+    pub id: String,
+    pub session_id: Option<String>,
+    pub tool_name: String,
+    pub tool_input: Value,
+    pub cwd: Option<String>, // Project the session runs in
+    pub requested_at: String,
+    pub expires_at: String,
+}
+
+/// An audited approval decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalDecision {
+    pub id: String,
+    pub session_id: Option<String>,
+    pub tool_name: String,
+    pub tool_input: Value,
+    pub cwd: Option<String>,
+    pub decision: String,   // "allow", "deny" or "ask"
+    pub decided_by: String, // "user" or "timeout"
+    pub reason: Option<String>,
+    pub requested_at: String,
+    pub decided_at: String,
+}
+
+struct Waiting {
+    approval: PendingApproval,
+    reply: mpsc::Sender<(String, Option<String>)>,
+}
+
+/// Tool calls currently waiting on the user, shared between hook threads and commands
+#[derive(Default)]
+pub struct Approvals {
+    waiting: Mutex<HashMap<String, Waiting>>,
+}
+
+impl Approvals {
+    /// Pending approvals, oldest first
+    pub fn pending(&self) -> Result<Vec<PendingApproval>, String> {
+        let waiting = self.waiting.lock().map_err(|e| e.to_string())?;
+
+        let mut pending: Vec<PendingApproval> =
+            waiting.values().map(|w| w.approval.clone()).collect();
+        pending.sort_by(|a, b| a.requested_at.cmp(&b.requested_at));
+
+        Ok(pending)
+    }
+
+    /// Answer a pending approval
+    pub fn resolve(&self, id: &str, decision: &str, reason: Option<String>) -> Result<(), String> {
+        if !APPROVAL_DECISIONS.contains(&decision) {
+            return Err("Invalid decision. Must be 'allow', 'deny', or 'ask'".to_string());
+        }
+
+        // Send while holding the lock, so `expire` either sees the answer or wins the race
+        let mut waiting = self.waiting.lock().map_err(|e| e.to_string())?;
+        let entry = waiting.remove(id).ok_or("Approval is no longer pending")?;
+
+        entry
+            .reply
+            .send((decision.to_string(), reason))
+            .map_err(|_| "Approval is no longer pending".to_string())
+    }
+
+    fn insert(&self, approval: PendingApproval, reply: mpsc::Sender<(String, Option<String>)>) {
+        if let Ok(mut waiting) = self.waiting.lock() {
+            waiting.insert(approval.id.clone(), Waiting { approval, reply });
+        }
+    }
+
+    /// Stop waiting on a timed out approval, returning an answer that arrived meanwhile
+    fn expire(
+        &self,
+        id: &str,
+        reply: &mpsc::Receiver<(String, Option<String>)>,
+    ) -> Option<(String, Option<String>)> {
+        if let Ok(mut waiting) = self.waiting.lock() {
+            waiting.remove(id);
+        }
+
+        reply.try_recv().ok()
+    }
+}
+
+/// PreToolUse hook output carrying a permission decision
+fn hook_reply(decision: &str, reason: Option<&str>) -> Value {
+    json!({
+        "hookSpecificOutput": {
+            "hookEventName": "PreToolUse",
+            "permissionDecision": decision,
+            "permissionDecisionReason": reason.unwrap_or("Decided in Tusker"),
+        }
+    })
+}
+
+/// Hold a PreToolUse hook until the user decides or the timeout applies the default
+///
+/// Emits `approval-requested` with the `PendingApproval` and `approval-resolved`
+/// with the audited `ApprovalDecision`. Returns the hook's reply.
+pub fn request(app: &AppHandle, event: &HookEvent, config: &Config) -> Result<Value, String> {
+    let timeout_secs = config.approval_timeout_secs.min(MAX_APPROVAL_TIMEOUT_SECS);
+    let now = chrono::Utc::now();
+
+    let approval = PendingApproval {
+        id: Uuid::new_v4().to_string(),
+        session_id: event.session_id.clone(),
+        tool_name: event.tool_name.clone().unwrap_or_default(),
+        tool_input: event.payload.get("tool_input").cloned().unwrap_or(Value::Null),
+        cwd: event.cwd.clone(),
+        requested_at: now.to_rfc3339(),
+        expires_at: (now + chrono::Duration::seconds(timeout_secs.into())).to_rfc3339(),
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let approvals = app.state::<Approvals>();
+    approvals.insert(approval.clone(), tx);
+
+    let _ = app.emit("approval-requested", &approval);
+    notify(app, &approval, config);
+
+    let (decision, decided_by, reason) =
+        match rx
+            .recv_timeout(Duration::from_secs(timeout_secs.into()))
+            .ok()
+            .or_else(|| approvals.expire(&approval.id, &rx))
+        {
+            Some((decision, reason)) => (decision, "user", reason),
+            None => (
+                config.approval_default.clone(),
+                "timeout",
+                Some("No answer in Tusker before the timeout".to_string()),
+            ),
+        };
+
+    let record = ApprovalDecision {
+        id: approval.id,
+        session_id: approval.session_id,
+        tool_name: approval.tool_name,
+        tool_input: approval.tool_input,
+        cwd: approval.cwd,
+        decision,
+        decided_by: decided_by.to_string(),
+        reason,
+        requested_at: approval.requested_at,
+        decided_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    {
+        let db = app.state::<Database>();
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        record_decision(&conn, &record)?;
+    }
+
+    let _ = app.emit("approval-resolved", &record);
+
+    Ok(hook_reply(&record.decision, record.reason.as_deref()))
+}
+
+/// Raise a notification so the user notices a waiting tool call
+fn notify(app: &AppHandle, approval: &PendingApproval, config: &Config) {
+    if !config.session_notifications {
+        return;
+    }
+
+    let project = approval
+        .cwd
+        .as_deref()
+        .and_then(|cwd| cwd.rsplit('/').next())
+        .unwrap_or("Claude");
+
+    let _ = notifications::raise(
+        app,
+        "warning",
+        "Approval needed",
+        &format!("{}: {} is waiting for approval", project, approval.tool_name),
+        None,
+        approval.session_id.as_deref(),
+        None,
+    );
+}
+
+fn record_decision(conn: &Connection, record: &ApprovalDecision) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO approval_decisions
+         (id, session_id, tool_name, tool_input, cwd, decision, decided_by, reason, requested_at, decided_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            &record.id,
+            &record.session_id,
+            &record.tool_name,
+            record.tool_input.to_string(),
+            &record.cwd,
+            &record.decision,
+            &record.decided_by,
+            &record.reason,
+            &record.requested_at,
+            &record.decided_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Audited decisions, newest first, optionally for one session
+pub fn list_decisions(
+    conn: &Connection,
+    session_id: Option<&str>,
+    limit: u32,
+) -> Result<Vec<ApprovalDecision>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, session_id, tool_name, tool_input, cwd, decision, decided_by, reason,
+                    requested_at, decided_at
+             FROM approval_decisions
+             WHERE ?1 IS NULL OR session_id = ?1
+             ORDER BY decided_at DESC
+             LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let decisions = stmt
+        .query_map(params![session_id, limit], |row| {
+            let tool_input: String = row.get(3)?;
+
+            Ok(ApprovalDecision {
+                id: row.get(0)?,
+                session_id: row.get(1)?,
+                tool_name: row.get(2)?,
+                tool_input: serde_json::from_str(&tool_input).unwrap_or(Value::Null),
+                cwd: row.get(4)?,
+                decision: row.get(5)?,
+                decided_by: row.get(6)?,
+                reason: row.get(7)?,
+                requested_at: row.get(8)?,
+                decided_at: row.get(9)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(decisions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approval(id: &str) -> PendingApproval {
+        PendingApproval {
+            id: id.to_string(),
+            session_id: None,
+            tool_name: "Bash".to_string(),
+            tool_input: json!({ "command": "rm -rf target" }),
+            cwd: None,
+            requested_at: String::new(),
+            expires_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_answers_waiting_hook_once() {
+        let approvals = Approvals::default();
+        let (tx, rx) = mpsc::channel();
+        approvals.insert(approval("a1"), tx);
+
+        assert!(approvals.resolve("a1", "maybe", None).is_err());
+        approvals.resolve("a1", "deny", Some("Not now".to_string())).unwrap();
+
+        assert_eq!(rx.recv().unwrap(), ("deny".to_string(), Some("Not now".to_string())));
+        assert!(approvals.pending().unwrap().is_empty());
+        assert!(approvals.resolve("a1", "allow", None).is_err());
+    }
+
+    #[test]
+    fn test_expire_keeps_answer_sent_at_timeout() {
+        let approvals = Approvals::default();
+        let (tx, rx) = mpsc::channel();
+        approvals.insert(approval("a1"), tx);
+
+        approvals.resolve("a1", "allow", None).unwrap();
+
+        assert_eq!(approvals.expire("a1", &rx), Some(("allow".to_string(), None)));
+        assert_eq!(approvals.expire("a1", &rx), None);
+    }
+
+    #[test]
+    fn test_hook_reply() {
+        let reply = hook_reply("allow", None);
+        assert_eq!(reply["hookSpecificOutput"]["permissionDecision"], "allow");
+        assert_eq!(reply["hookSpecificOutput"]["hookEventName"], "PreToolUse");
+    }
+}
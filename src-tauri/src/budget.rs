@@ -0,0 +1,175 @@
+use chrono::{Duration, SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::claude::index::FIRST_COPY;
+use crate::claude::timezone::DayZone;
+use crate::models::Config;
+
+/// Today's token usage measured against `Config.daily_token_limit`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    pub date: String,
+    pub daily_token_limit: u32,
+    pub tokens_used: u64,
+    pub percent_used: f64,
+    pub projected_tokens: u64, // End-of-day usage if the current rate continues
+    pub projected_percent: f64,
+    pub thresholds: Vec<u32>,
+    pub crossed_thresholds: Vec<u32>,
+}
+
+/// A threshold crossed for the first time today
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetAlert {
+    pub threshold: u32,
+    pub status: BudgetStatus,
+}
+
+/// Percentage of the limit, zero when there is no limit
+fn percent_of(tokens: u64, limit: u32) -> f64 {
+    if limit == 0 {
+        0.0
+    } else {
+        tokens as f64 * 100.0 / limit as f64
+    }
+}
+
+/// Extrapolate usage so far to the whole day
+fn project_end_of_day(tokens_used: u64, day_elapsed: f64) -> u64 {
+    // Too early in the day for the rate to mean anything
+    if day_elapsed < 1.0 / 24.0 {
+        return tokens_used;
+    }
+
+    (tokens_used as f64 / day_elapsed.min(1.0)).round() as u64
+}
+
+/// Input and output tokens of indexed messages on a local date
+///
+/// Only sessions of tracked projects are indexed, matching what Tusker shows.
+fn tokens_used_on(conn: &Connection, date: &str, zone: DayZone) -> Result<u64, String> {
+    zone.register_sql_functions(conn)?;
+
+    // Any local date lies within a day of its UTC date, narrow the scan with the timestamp index
+    let since = (Utc::now() - Duration::days(2)).to_rfc3339_opts(SecondsFormat::Millis, true);
+
+    let tokens: Option<i64> = conn
+        .query_row(
+            &format!(
+                "SELECT SUM(COALESCE(m.input_tokens, 0) + COALESCE(m.output_tokens, 0))
+                 FROM messages m
+                 WHERE m.timestamp >= ?1 AND local_date(m.timestamp) = ?2 AND {}",
+                FIRST_COPY
+            ),
+            params![&since, date],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(tokens.unwrap_or(0).max(0) as u64)
+}
+
+/// Evaluate today's usage against the configured limit and thresholds
+pub fn get_status(conn: &Connection, config: &Config, zone: DayZone) -> Result<BudgetStatus, String> {
+    let date = zone.today();
+    let tokens_used = tokens_used_on(conn, &date, zone)?;
+    let projected_tokens = project_end_of_day(tokens_used, zone.day_elapsed());
+    let percent_used = percent_of(tokens_used, config.daily_token_limit);
+
+    let mut thresholds = config.budget_thresholds.clone();
+    thresholds.sort_unstable();
+    thresholds.dedup();
+
+    let crossed_thresholds = if config.daily_token_limit == 0 {
+        vec![]
+    } else {
+        thresholds
+            .iter()
+            .copied()
+            .filter(|t| percent_used >= *t as f64)
+            .collect()
+    };
+
+    Ok(BudgetStatus {
+        date,
+        daily_token_limit: config.daily_token_limit,
+        tokens_used,
+        percent_used,
+        projected_tokens,
+        projected_percent: percent_of(projected_tokens, config.daily_token_limit),
+        thresholds,
+        crossed_thresholds,
+    })
+}
+
+/// Record thresholds crossed today and return those not alerted on before
+pub fn check_thresholds(
+    conn: &Connection,
+    config: &Config,
+    zone: DayZone,
+) -> Result<Vec<BudgetAlert>, String> {
+    let status = get_status(conn, config, zone)?;
+    let mut alerts = vec![];
+
+    for threshold in &status.crossed_thresholds {
+        let inserted = conn
+            .execute(
+                "INSERT OR IGNORE INTO budget_alerts (date, threshold, tokens_used, daily_token_limit)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![&status.date, threshold, status.tokens_used as i64, status.daily_token_limit],
+            )
+            .map_err(|e| e.to_string())?;
+
+        if inserted > 0 {
+            alerts.push(BudgetAlert {
+                threshold: *threshold,
+                status: status.clone(),
+            });
+        }
+    }
+
+    Ok(alerts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_end_of_day() {
+        assert_eq!(project_end_of_day(10_000, 0.5), 20_000);
+        assert_eq!(project_end_of_day(10_000, 0.25), 40_000);
+        // Not extrapolated in the first hour
+        assert_eq!(project_end_of_day(10_000, 0.01), 10_000);
+    }
+
+    #[test]
+    fn test_percent_of_without_limit() {
+        assert_eq!(percent_of(40_000, 50_000), 80.0);
+        assert_eq!(percent_of(40_000, 0), 0.0);
+    }
+
+    #[test]
+    fn test_resumed_history_counted_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_schema(&conn).unwrap();
+        conn.execute("INSERT INTO session_files (path, project_path) VALUES ('s.jsonl', '/work/app')", [])
+            .unwrap();
+
+        let zone = DayZone::from_name(Some("UTC")).unwrap();
+        let now = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+        // s2 resumes s1 and repeats u1
+        for (uuid, session_id) in [("u1", "s1"), ("u1", "s2"), ("u2", "s2")] {
+            conn.execute(
+                "INSERT INTO messages
+                 (uuid, session_id, msg_type, content, input_tokens, output_tokens, timestamp, file_path, line_offset)
+                 VALUES (?1, ?2, 'assistant', '', 100, 50, ?3, 's.jsonl', 0)",
+                params![uuid, session_id, now],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(tokens_used_on(&conn, &zone.date(&Utc::now()), zone).unwrap(), 300);
+    }
+}
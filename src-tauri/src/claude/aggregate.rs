@@ -0,0 +1,278 @@
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::stats::{DailyActivity, LongestSession, ModelUsage, StatsSource};
+use super::timezone::DayZone;
+
+/// Usage statistics aggregated from indexed session messages
+#[derive(Debug, Default)]
+pub struct SessionStats {
+    pub daily_activity: Vec<DailyActivity>,
+    pub daily_model_tokens: BTreeMap<String, HashMap<String, u64>>,
+    pub model_usage: HashMap<String, ModelUsage>,
+    pub hour_counts: HashMap<String, u32>,
+    pub total_sessions: u32,
+    pub total_messages: u32,
+    pub longest_session: Option<LongestSession>,
+    pub first_session_date: Option<String>,
+}
+
+/// Running totals for a single day
+#[derive(Default)]
+struct DayTotals {
+    message_count: u32,
+    sessions: HashSet<String>,
+    tool_call_count: u32,
+}
+
+/// An indexed message's usage
+struct MessageUsage {
+    uuid: String,
+    session_id: String,
+    timestamp: String,
+    model: Option<String>,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_read_input_tokens: u64,
+    cache_creation_input_tokens: u64,
+}
+
+/// A timestamp as stats report it, RFC 3339 in UTC with milliseconds
+pub fn format_timestamp<T: chrono::TimeZone>(time: &chrono::DateTime<T>) -> String {
+    time.with_timezone(&chrono::Utc)
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// Aggregate stats from the indexed messages of every project
+///
+/// Days and hours are bucketed in `zone`. When `after` (`YYYY-MM-DD`) is given,
+/// only messages on later days are counted, and only sessions that started
+/// after that day count towards `total_sessions`, so sessions spanning the
+/// day are not counted twice when added to stats up to it.
+pub fn compute_session_stats(conn: &Connection, after: Option<&str>, zone: DayZone) -> Result<SessionStats, String> {
+    zone.register_sql_functions(conn)?;
+
+    let mut days: BTreeMap<String, DayTotals> = BTreeMap::new();
+    let mut stats = SessionStats::default();
+    let mut seen_messages: HashSet<String> = HashSet::new();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT uuid, session_id, timestamp, model,
+                    COALESCE(input_tokens, 0), COALESCE(output_tokens, 0),
+                    COALESCE(cache_read_input_tokens, 0), COALESCE(cache_creation_input_tokens, 0)
+             FROM messages
+             WHERE ?1 IS NULL OR local_date(timestamp) > ?1
+             ORDER BY timestamp, id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let messages = stmt
+        .query_map(params![after], |row| {
+            Ok(MessageUsage {
+                uuid: row.get(0)?,
+                session_id: row.get(1)?,
+                timestamp: row.get(2)?,
+                model: row.get(3)?,
+                input_tokens: row.get(4)?,
+                output_tokens: row.get(5)?,
+                cache_read_input_tokens: row.get(6)?,
+                cache_creation_input_tokens: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    for msg in messages {
+        let msg = msg.map_err(|e| e.to_string())?;
+        let timestamp = match chrono::DateTime::parse_from_rfc3339(&msg.timestamp) {
+            Ok(ts) => ts,
+            Err(_) => continue,
+        };
+
+        // Resumed sessions repeat earlier messages under their own id
+        if !seen_messages.insert(msg.uuid.clone()) {
+            continue;
+        }
+
+        record_message(&mut stats, &mut days, &zone.date(&timestamp), &zone.hour(&timestamp), &msg);
+    }
+
+    // Tool call ids are unique, copies in resumed sessions are counted once
+    let mut stmt = conn
+        .prepare(
+            "SELECT local_date(timestamp) AS day, COUNT(DISTINCT id)
+             FROM tool_calls
+             WHERE ?1 IS NULL OR local_date(timestamp) > ?1
+             GROUP BY day",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let tool_calls = stmt
+        .query_map(params![after], |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, u32>(1)?)))
+        .map_err(|e| e.to_string())?;
+
+    for row in tool_calls {
+        if let (Some(date), count) = row.map_err(|e| e.to_string())? {
+            if let Some(day) = days.get_mut(&date) {
+                day.tool_call_count = count;
+            }
+        }
+    }
+
+    stats.daily_activity = days
+        .into_iter()
+        .map(|(date, totals)| DailyActivity {
+            date,
+            message_count: totals.message_count,
+            session_count: totals.sessions.len() as u32,
+            tool_call_count: totals.tool_call_count,
+            source: StatsSource::Sessions,
+        })
+        .collect();
+
+    record_sessions(conn, &mut stats, after, zone)?;
+
+    Ok(stats)
+}
+
+/// Count the sessions that started after `after` and find the longest active one
+///
+/// Spans cover every message of a session, including those up to `after`.
+fn record_sessions(
+    conn: &Connection,
+    stats: &mut SessionStats,
+    after: Option<&str>,
+    zone: DayZone,
+) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT session_id, MIN(timestamp), MAX(timestamp), COUNT(*)
+             FROM messages
+             GROUP BY session_id
+             HAVING ?1 IS NULL OR local_date(MAX(timestamp)) > ?1",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let spans = stmt
+        .query_map(params![after], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, u32>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut first_session: Option<chrono::DateTime<chrono::FixedOffset>> = None;
+
+    for span in spans {
+        let (session_id, first, last, message_count) = span.map_err(|e| e.to_string())?;
+        let (first, last) = match (
+            chrono::DateTime::parse_from_rfc3339(&first),
+            chrono::DateTime::parse_from_rfc3339(&last),
+        ) {
+            (Ok(first), Ok(last)) => (first, last),
+            _ => continue,
+        };
+
+        if after.is_none_or(|after| zone.date(&first).as_str() > after) {
+            stats.total_sessions += 1;
+            first_session = Some(first_session.map_or(first, |earliest| earliest.min(first)));
+        }
+
+        let duration = (last - first).num_milliseconds().max(0) as u64;
+        if stats.longest_session.as_ref().is_none_or(|longest| duration > longest.duration) {
+            stats.longest_session = Some(LongestSession {
+                session_id,
+                duration,
+                message_count,
+                timestamp: format_timestamp(&first),
+            });
+        }
+    }
+
+    stats.first_session_date = first_session.map(|first| format_timestamp(&first));
+
+    Ok(())
+}
+
+/// Add a single message to the running totals
+fn record_message(
+    stats: &mut SessionStats,
+    days: &mut BTreeMap<String, DayTotals>,
+    date: &str,
+    hour: &str,
+    msg: &MessageUsage,
+) {
+    let day = days.entry(date.to_string()).or_default();
+    day.message_count += 1;
+    day.sessions.insert(msg.session_id.clone());
+
+    stats.total_messages += 1;
+    *stats.hour_counts.entry(hour.to_string()).or_default() += 1;
+
+    if let Some(model) = &msg.model {
+        *stats
+            .daily_model_tokens
+            .entry(date.to_string())
+            .or_default()
+            .entry(model.clone())
+            .or_default() += msg.input_tokens + msg.output_tokens;
+
+        let usage = stats.model_usage.entry(model.clone()).or_default();
+        usage.input_tokens += msg.input_tokens;
+        usage.output_tokens += msg.output_tokens;
+        usage.cache_read_input_tokens += msg.cache_read_input_tokens;
+        usage.cache_creation_input_tokens += msg.cache_creation_input_tokens;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO session_files (path, project_path) VALUES ('session.jsonl', '/work/app')",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn insert(conn: &Connection, uuid: &str, session_id: &str, timestamp: &str) {
+        conn.execute(
+            "INSERT INTO messages
+             (uuid, session_id, msg_type, content, model, input_tokens, output_tokens, timestamp, file_path, line_offset)
+             VALUES (?1, ?2, 'assistant', '', 'claude-sonnet-4', 10, 5, ?3, 'session.jsonl', 0)",
+            params![uuid, session_id, timestamp],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_sessions_spanning_the_cutoff_count_once() {
+        let conn = open_db();
+        let zone = DayZone::from_name(Some("UTC")).unwrap();
+        insert(&conn, "u1", "s1", "2026-03-01T23:00:00.000Z");
+        insert(&conn, "u2", "s1", "2026-03-02T01:00:00.000Z");
+        insert(&conn, "u3", "s2", "2026-03-02T09:00:00.000Z");
+        insert(&conn, "u3", "s3", "2026-03-02T09:00:00.000Z");
+
+        let stats = compute_session_stats(&conn, Some("2026-03-01"), zone).unwrap();
+
+        // s1 started on the cutoff day, s3 copied s2's message
+        assert_eq!(stats.total_sessions, 2);
+        assert_eq!(stats.total_messages, 2);
+        assert_eq!(stats.first_session_date.as_deref(), Some("2026-03-02T09:00:00.000Z"));
+        assert_eq!(stats.model_usage["claude-sonnet-4"].input_tokens, 20);
+
+        let longest = stats.longest_session.unwrap();
+        assert_eq!(longest.session_id, "s1");
+        assert_eq!(longest.duration, 2 * 60 * 60 * 1000);
+        assert_eq!(longest.timestamp, "2026-03-01T23:00:00.000Z");
+    }
+}
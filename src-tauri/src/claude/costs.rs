@@ -0,0 +1,270 @@
+use rusqlite::{params, Connection, ToSql};
+use serde::{Deserialize, Serialize};
+
+use super::index::FIRST_COPY;
+use super::pricing::{PricingTable, TokenUsage};
+use super::timezone::DayZone;
+
+/// Priced token usage of a single message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageCost {
+    pub uuid: String,
+    pub model: Option<String>,
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+}
+
+/// Priced token usage aggregated under a key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostSummary {
+    pub key: String, // Session id, project path or date depending on the query
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+}
+
+/// Token sums of the indexed messages, as selected by every cost query
+const USAGE_COLUMNS: &str = "SUM(COALESCE(m.input_tokens, 0)), SUM(COALESCE(m.output_tokens, 0)),
+     SUM(COALESCE(m.cache_read_input_tokens, 0)), SUM(COALESCE(m.cache_creation_input_tokens, 0))";
+
+fn row_to_usage(row: &rusqlite::Row, start: usize) -> rusqlite::Result<TokenUsage> {
+    Ok(TokenUsage {
+        input_tokens: row.get::<_, Option<u64>>(start)?.unwrap_or(0),
+        output_tokens: row.get::<_, Option<u64>>(start + 1)?.unwrap_or(0),
+        cache_read_input_tokens: row.get::<_, Option<u64>>(start + 2)?.unwrap_or(0),
+        cache_creation_input_tokens: row.get::<_, Option<u64>>(start + 3)?.unwrap_or(0),
+    })
+}
+
+/// Run a query yielding `(key, model, usage...)` rows and price each group
+///
+/// Rows for the same key must be adjacent; keys keep the query's order.
+fn priced_groups(
+    conn: &Connection,
+    pricing: &PricingTable,
+    sql: &str,
+    params: &[&dyn ToSql],
+) -> Result<Vec<CostSummary>, String> {
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row_to_usage(row, 2)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut summaries: Vec<CostSummary> = vec![];
+
+    for row in rows {
+        let (key, model, usage) = row.map_err(|e| e.to_string())?;
+        let cost = model
+            .as_deref()
+            .map(|m| pricing.cost(m, &usage))
+            .unwrap_or(0.0);
+
+        match summaries.last_mut() {
+            Some(last) if last.key == key => {
+                last.usage.add(&usage);
+                last.cost_usd += cost;
+            }
+            _ => summaries.push(CostSummary {
+                key,
+                usage,
+                cost_usd: cost,
+            }),
+        }
+    }
+
+    Ok(summaries)
+}
+
+/// Cost of every message in a session, in order
+///
+/// Like every cost query, messages a resumed session copied from an earlier
+/// one are only priced where they were first indexed.
+pub fn message_costs(
+    conn: &Connection,
+    pricing: &PricingTable,
+    session_id: &str,
+) -> Result<Vec<MessageCost>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT m.uuid, m.model, m.timestamp, m.input_tokens, m.output_tokens,
+                    m.cache_read_input_tokens, m.cache_creation_input_tokens
+             FROM messages m
+             WHERE m.session_id = ?1 AND {}
+             ORDER BY m.timestamp",
+            FIRST_COPY
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let costs = stmt
+        .query_map(params![session_id], |row| {
+            let model: Option<String> = row.get(1)?;
+            let usage = row_to_usage(row, 3)?;
+            let cost_usd = model
+                .as_deref()
+                .map(|m| pricing.cost(m, &usage))
+                .unwrap_or(0.0);
+
+            Ok(MessageCost {
+                uuid: row.get(0)?,
+                model,
+                timestamp: row.get(2)?,
+                usage,
+                cost_usd,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(costs)
+}
+
+/// Total cost of a session
+pub fn session_cost(
+    conn: &Connection,
+    pricing: &PricingTable,
+    session_id: &str,
+) -> Result<CostSummary, String> {
+    let summaries = priced_groups(
+        conn,
+        pricing,
+        &format!(
+            "SELECT m.session_id, m.model, {}
+             FROM messages m
+             WHERE m.session_id = ?1 AND {}
+             GROUP BY m.model",
+            USAGE_COLUMNS, FIRST_COPY
+        ),
+        params![session_id],
+    )?;
+
+    Ok(summaries.into_iter().next().unwrap_or(CostSummary {
+        key: session_id.to_string(),
+        usage: TokenUsage::default(),
+        cost_usd: 0.0,
+    }))
+}
+
+/// Total cost of the sessions linked to a task, keyed by task id
+///
+/// Messages repeated across the linked sessions are priced once.
+pub fn task_cost(conn: &Connection, pricing: &PricingTable, task_id: &str) -> Result<CostSummary, String> {
+    let summaries = priced_groups(
+        conn,
+        pricing,
+        &format!(
+            "SELECT ts.task_id, m.model, {}
+             FROM messages m
+             JOIN task_sessions ts ON ts.session_id = m.session_id
+             WHERE ts.task_id = ?1 AND {}
+             GROUP BY m.model",
+            USAGE_COLUMNS, FIRST_COPY
+        ),
+        params![task_id],
+    )?;
+
+    Ok(summaries.into_iter().next().unwrap_or(CostSummary {
+        key: task_id.to_string(),
+        usage: TokenUsage::default(),
+        cost_usd: 0.0,
+    }))
+}
+
+/// Total cost of each tracked project, keyed by project path
+pub fn project_costs(conn: &Connection, pricing: &PricingTable) -> Result<Vec<CostSummary>, String> {
+    priced_groups(
+        conn,
+        pricing,
+        &format!(
+            "SELECT s.project_path, m.model, {}
+             FROM messages m
+             JOIN sessions s ON s.id = m.session_id
+             WHERE s.project_path IN (SELECT path FROM projects) AND {}
+             GROUP BY s.project_path, m.model
+             ORDER BY s.project_path",
+            USAGE_COLUMNS, FIRST_COPY
+        ),
+        params![],
+    )
+}
+
+/// Cost per local day across tracked projects, optionally for one project, keyed by `YYYY-MM-DD`
+pub fn daily_costs(
+    conn: &Connection,
+    pricing: &PricingTable,
+    project_path: Option<&str>,
+    zone: DayZone,
+) -> Result<Vec<CostSummary>, String> {
+    zone.register_sql_functions(conn)?;
+
+    priced_groups(
+        conn,
+        pricing,
+        &format!(
+            "SELECT local_date(m.timestamp) AS day, m.model, {}
+             FROM messages m
+             JOIN sessions s ON s.id = m.session_id
+             WHERE s.project_path IN (SELECT path FROM projects)
+               AND (?1 IS NULL OR s.project_path = ?1)
+               AND {}
+             GROUP BY day, m.model
+             ORDER BY day",
+            USAGE_COLUMNS, FIRST_COPY
+        ),
+        params![project_path],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert(conn: &Connection, uuid: &str, session_id: &str, output_tokens: u64) {
+        conn.execute(
+            "INSERT INTO messages
+             (uuid, session_id, msg_type, content, model, input_tokens, output_tokens, timestamp, file_path, line_offset)
+             VALUES (?1, ?2, 'assistant', '', 'claude-sonnet-4-5', 0, ?3, '2026-03-01T10:00:00.000Z', ?2, 0)",
+            params![uuid, session_id, output_tokens],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_resumed_history_priced_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, path) VALUES ('p1', 'app', '/work/app');
+             INSERT INTO session_files (path, project_path) VALUES ('s1', '/work/app'), ('s2', '/work/app');
+             INSERT INTO sessions (id, project_path, project_name, first_message, started_at, last_activity)
+             VALUES ('s1', '/work/app', 'app', '', '', ''), ('s2', '/work/app', 'app', '', '', '');",
+        )
+        .unwrap();
+
+        // s2 resumes s1, copying its message before adding its own
+        insert(&conn, "u1", "s1", 1_000_000);
+        insert(&conn, "u1", "s2", 1_000_000);
+        insert(&conn, "u2", "s2", 2_000_000);
+
+        let pricing = PricingTable::builtin();
+        let projects = project_costs(&conn, &pricing).unwrap();
+        assert_eq!(projects[0].usage.output_tokens, 3_000_000);
+        assert!((projects[0].cost_usd - 45.0).abs() < 1e-9);
+
+        assert_eq!(session_cost(&conn, &pricing, "s2").unwrap().usage.output_tokens, 2_000_000);
+        assert_eq!(message_costs(&conn, &pricing, "s2").unwrap().len(), 1);
+
+        let zone = DayZone::from_name(Some("UTC")).unwrap();
+        let days = daily_costs(&conn, &pricing, None, zone).unwrap();
+        assert_eq!(days[0].usage.output_tokens, 3_000_000);
+    }
+}
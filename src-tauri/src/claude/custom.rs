@@ -0,0 +1,594 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::paths::{get_claude_home, write_atomic};
+use super::settings::validate_rule;
+
+/// Which kind of Markdown definition Claude loads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomKind {
+    Command, // Slash command in .claude/commands
+    Agent,   // Subagent in .claude/agents
+}
+
+impl CustomKind {
+    fn dir(&self) -> &'static str {
+        match self {
+            CustomKind::Command => "commands",
+            CustomKind::Agent => "agents",
+        }
+    }
+}
+
+/// Whether a definition is shared by all projects or belongs to one
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomScope {
+    User,    // ~/.claude
+    Project, // <project>/.claude
+}
+
+/// Editable fields of a slash command or subagent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomDefinition {
+    pub description: Option<String>,
+    pub allowed_tools: Vec<String>, // `allowed-tools` for commands, `tools` for agents
+    pub model: Option<String>,
+    pub argument_hint: Option<String>, // Commands only
+    pub body: String,                  // Prompt, or the agent's system prompt
+}
+
+/// A slash command or subagent file and how often it was used in the project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomFile {
+    pub kind: CustomKind,
+    pub scope: CustomScope,
+    pub name: String,
+    pub namespace: Option<String>, // Subdirectory of a command, e.g. "frontend"
+    pub path: String,
+    pub definition: CustomDefinition,
+    pub invocations: u32,
+    pub last_used: Option<String>,
+    pub problems: Vec<String>,
+}
+
+/// Parsed frontmatter entries in file order, as key and raw value
+type Frontmatter = Vec<(String, String)>;
+
+/// Invocation count and latest use per kind and name
+type Usage = HashMap<(CustomKind, String), (u32, String)>;
+
+fn custom_dir(kind: CustomKind, scope: CustomScope, project_path: &str) -> Result<PathBuf, String> {
+    let base = match scope {
+        CustomScope::User => get_claude_home().ok_or("Could not find home directory")?,
+        CustomScope::Project => Path::new(project_path).join(".claude"),
+    };
+
+    Ok(base.join(kind.dir()))
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')));
+
+    if quoted {
+        value[1..value.len() - 1].replace("\\\"", "\"").replace("\\\\", "\\")
+    } else {
+        value.to_string()
+    }
+}
+
+fn yaml_string(value: &str) -> String {
+    let needs_quotes = value.contains(": ")
+        || value.contains(" #")
+        || value.starts_with(|c: char| "[]{}&*!|>'\"%@`#,?:-".contains(c));
+
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Split a comma separated list, keeping commas inside parentheses
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = vec![];
+    let mut depth = 0;
+    let mut current = String::new();
+
+    for c in value.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    items.push(current);
+
+    items
+        .iter()
+        .map(|item| unquote(item))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Parse a frontmatter list: `a, b`, `[a, b]` or YAML `- a` lines
+fn parse_list(value: &str) -> Vec<String> {
+    let value = value.trim();
+
+    if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return split_list(inner);
+    }
+    if value.lines().any(|line| line.trim_start().starts_with("- ")) {
+        return value
+            .lines()
+            .filter_map(|line| line.trim_start().strip_prefix("- "))
+            .map(unquote)
+            .collect();
+    }
+
+    split_list(value)
+}
+
+/// Split `---` frontmatter from the body
+fn parse_frontmatter(content: &str) -> Result<(Frontmatter, String), String> {
+    let rest = match content.strip_prefix("---\n").or_else(|| content.strip_prefix("---\r\n")) {
+        Some(rest) => rest,
+        None => return Ok((vec![], content.to_string())),
+    };
+
+    let mut entries: Frontmatter = vec![];
+    let mut lines = rest.lines();
+
+    loop {
+        let line = lines
+            .next()
+            .ok_or("Frontmatter is missing its closing ---")?;
+        if line.trim_end() == "---" {
+            break;
+        }
+
+        // Indented lines continue the previous value, e.g. YAML list items
+        if line.starts_with([' ', '\t', '-']) {
+            if let Some((_, value)) = entries.last_mut() {
+                value.push('\n');
+                value.push_str(line);
+                continue;
+            }
+        }
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("Frontmatter line '{}' is not `key: value`", line))?;
+        entries.push((key.trim().to_string(), value.trim().to_string()));
+    }
+
+    let body = lines.collect::<Vec<_>>().join("\n");
+    Ok((entries, body.trim_start_matches('\n').to_string()))
+}
+
+fn entry<'a>(frontmatter: &'a Frontmatter, key: &str) -> Option<&'a str> {
+    frontmatter
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+fn tools_key(kind: CustomKind) -> &'static str {
+    match kind {
+        CustomKind::Command => "allowed-tools",
+        CustomKind::Agent => "tools",
+    }
+}
+
+fn to_definition(kind: CustomKind, frontmatter: &Frontmatter, body: String) -> CustomDefinition {
+    let text = |key| entry(frontmatter, key).map(unquote).filter(|v| !v.is_empty());
+
+    CustomDefinition {
+        description: text("description"),
+        allowed_tools: entry(frontmatter, tools_key(kind))
+            .map(parse_list)
+            .unwrap_or_default(),
+        model: text("model"),
+        argument_hint: text("argument-hint"),
+        body,
+    }
+}
+
+/// Write a definition back, keeping frontmatter keys Tusker doesn't edit
+fn render(kind: CustomKind, name: &str, definition: &CustomDefinition, frontmatter: &Frontmatter) -> String {
+    let mut known: Frontmatter = vec![];
+    if kind == CustomKind::Agent {
+        known.push(("name".to_string(), name.to_string()));
+    }
+    if let Some(description) = &definition.description {
+        known.push(("description".to_string(), yaml_string(description)));
+    }
+    if !definition.allowed_tools.is_empty() {
+        known.push((tools_key(kind).to_string(), definition.allowed_tools.join(", ")));
+    }
+    if let Some(hint) = definition.argument_hint.as_ref().filter(|_| kind == CustomKind::Command) {
+        known.push(("argument-hint".to_string(), yaml_string(hint)));
+    }
+    if let Some(model) = &definition.model {
+        known.push(("model".to_string(), model.clone()));
+    }
+
+    let edited = ["name", "description", "allowed-tools", "tools", "argument-hint", "model"];
+    known.extend(
+        frontmatter
+            .iter()
+            .filter(|(key, _)| !edited.contains(&key.as_str()))
+            .cloned(),
+    );
+
+    if known.is_empty() {
+        return definition.body.clone();
+    }
+
+    let mut content = String::from("---\n");
+    for (key, value) in known {
+        content.push_str(&format!("{}: {}\n", key, value));
+    }
+    content.push_str("---\n\n");
+    content.push_str(&definition.body);
+    content
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// Problems Claude would have loading a definition
+fn validate(kind: CustomKind, name: &str, definition: &CustomDefinition) -> Vec<String> {
+    let mut problems = vec![];
+
+    if !valid_name(name) {
+        problems.push(format!(
+            "Name '{}' must use lowercase letters, digits, '-' or '_'",
+            name
+        ));
+    }
+    if kind == CustomKind::Agent && definition.description.is_none() {
+        problems.push("Agents need a description so Claude knows when to use them".to_string());
+    }
+    if definition.model.as_ref().is_some_and(|m| m.contains(char::is_whitespace)) {
+        problems.push("Model must be a single model name, e.g. sonnet".to_string());
+    }
+    for tool in &definition.allowed_tools {
+        if let Err(e) = validate_rule(tool) {
+            problems.push(e);
+        }
+    }
+    if definition.body.trim().is_empty() {
+        problems.push("The prompt is empty".to_string());
+    }
+
+    problems
+}
+
+fn markdown_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let mut entries: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+        Err(_) => return,
+    };
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            markdown_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            files.push(path);
+        }
+    }
+}
+
+fn read_file(kind: CustomKind, scope: CustomScope, dir: &Path, path: &Path) -> CustomFile {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let namespace = path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(dir).ok())
+        .map(|ns| ns.to_string_lossy().replace(std::path::MAIN_SEPARATOR, ":"))
+        .filter(|ns| !ns.is_empty());
+
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let (frontmatter, definition, mut problems) = match parse_frontmatter(&content) {
+        Ok((frontmatter, body)) => {
+            let definition = to_definition(kind, &frontmatter, body);
+            (frontmatter, definition, vec![])
+        }
+        Err(e) => (vec![], CustomDefinition { body: content.clone(), ..Default::default() }, vec![e]),
+    };
+
+    // Agents are named by their frontmatter, commands by their file
+    let name = match kind {
+        CustomKind::Agent => entry(&frontmatter, "name").map(unquote).unwrap_or(stem),
+        CustomKind::Command => stem,
+    };
+    problems.extend(validate(kind, &name, &definition));
+
+    CustomFile {
+        kind,
+        scope,
+        name,
+        namespace,
+        path: path.to_string_lossy().to_string(),
+        definition,
+        invocations: 0,
+        last_used: None,
+        problems,
+    }
+}
+
+/// Slash command named in a `<command-name>` tag of a user message
+fn invoked_command(content: &str) -> Option<&str> {
+    let start = content.find("<command-name>")? + "<command-name>".len();
+    let end = start + content[start..].find("</command-name>")?;
+
+    Some(content[start..end].trim().trim_start_matches('/'))
+}
+
+/// Invocations of slash commands and subagents in a project's sessions, with the latest use
+fn usage(conn: &Connection, project_path: &str) -> Result<Usage, String> {
+    let mut usage = Usage::new();
+    let mut record = |kind, name: &str, timestamp: String| {
+        let (count, last_used) = usage.entry((kind, name.to_string())).or_default();
+        *count += 1;
+        if timestamp > *last_used {
+            *last_used = timestamp;
+        }
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.content, m.timestamp
+             FROM messages m
+             JOIN sessions s ON s.id = m.session_id
+             WHERE s.project_path = ?1 AND m.msg_type = 'user' AND m.content LIKE '%<command-name>%'",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![project_path], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    for (content, timestamp) in rows {
+        if let Some(name) = invoked_command(&content) {
+            record(CustomKind::Command, name, timestamp);
+        }
+    }
+
+    // Subagents run through the Task tool
+    let mut stmt = conn
+        .prepare(
+            "SELECT tc.input, tc.timestamp
+             FROM tool_calls tc
+             JOIN sessions s ON s.id = tc.session_id
+             WHERE s.project_path = ?1 AND tc.name = 'Task'",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![project_path], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    for (input, timestamp) in rows {
+        let input: Value = serde_json::from_str(&input).unwrap_or(Value::Null);
+        if let Some(agent) = input.get("subagent_type").and_then(|v| v.as_str()) {
+            record(CustomKind::Agent, agent, timestamp);
+        }
+    }
+
+    Ok(usage)
+}
+
+/// Slash commands and subagents available in a project, with how often each was used
+pub fn list(conn: &Connection, project_path: &str) -> Result<Vec<CustomFile>, String> {
+    let usage = usage(conn, project_path)?;
+    let mut files = vec![];
+
+    for scope in [CustomScope::Project, CustomScope::User] {
+        for kind in [CustomKind::Command, CustomKind::Agent] {
+            let dir = custom_dir(kind, scope, project_path)?;
+            let mut paths = vec![];
+            markdown_files(&dir, &mut paths);
+
+            for path in paths {
+                let mut file = read_file(kind, scope, &dir, &path);
+
+                let keys = [
+                    Some(file.name.clone()),
+                    file.namespace.as_ref().map(|ns| format!("{}:{}", ns, file.name)),
+                ];
+                for key in keys.into_iter().flatten() {
+                    if let Some((count, last_used)) = usage.get(&(kind, key)) {
+                        file.invocations += count;
+                        file.last_used = file.last_used.max(Some(last_used.clone()));
+                    }
+                }
+
+                files.push(file);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Kind, scope and directory of a definition file, refusing paths outside Claude's directories
+fn locate(project_path: &str, path: &Path) -> Result<(CustomKind, CustomScope, PathBuf), String> {
+    let plain = path.is_absolute()
+        && path.extension().is_some_and(|ext| ext == "md")
+        && !path.components().any(|c| c == Component::ParentDir);
+
+    if plain {
+        for scope in [CustomScope::Project, CustomScope::User] {
+            for kind in [CustomKind::Command, CustomKind::Agent] {
+                let dir = custom_dir(kind, scope, project_path)?;
+                if path.starts_with(&dir) {
+                    return Ok((kind, scope, dir));
+                }
+            }
+        }
+    }
+
+    Err(format!("{} is not a slash command or agent file", path.display()))
+}
+
+fn check(kind: CustomKind, name: &str, definition: &CustomDefinition) -> Result<(), String> {
+    let problems = validate(kind, name, definition);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems.join("; "))
+    }
+}
+
+/// Create a slash command or subagent
+pub fn create(
+    project_path: &str,
+    kind: CustomKind,
+    scope: CustomScope,
+    name: &str,
+    namespace: Option<&str>,
+    definition: &CustomDefinition,
+) -> Result<PathBuf, String> {
+    check(kind, name, definition)?;
+
+    let mut dir = custom_dir(kind, scope, project_path)?;
+    if let Some(namespace) = namespace.filter(|ns| !ns.is_empty()) {
+        if kind == CustomKind::Agent {
+            return Err("Agents can't be namespaced".to_string());
+        }
+        for part in namespace.split([':', '/']) {
+            if !valid_name(part) {
+                return Err(format!("Namespace '{}' must use lowercase letters, digits, '-' or '_'", namespace));
+            }
+            dir.push(part);
+        }
+    }
+
+    let path = dir.join(format!("{}.md", name));
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+
+    write_atomic(&path, &render(kind, name, definition, &Frontmatter::new()))?;
+    Ok(path)
+}
+
+/// Replace the fields of a slash command or subagent
+pub fn update(project_path: &str, path: &str, definition: &CustomDefinition) -> Result<(), String> {
+    let path = Path::new(path);
+    let (kind, scope, dir) = locate(project_path, path)?;
+
+    let current = read_file(kind, scope, &dir, path);
+    check(kind, &current.name, definition)?;
+
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let (frontmatter, _) = parse_frontmatter(&content).unwrap_or_default();
+
+    write_atomic(path, &render(kind, &current.name, definition, &frontmatter))
+}
+
+/// Rename a slash command or subagent, returning its new path
+pub fn rename(project_path: &str, path: &str, new_name: &str) -> Result<PathBuf, String> {
+    let path = Path::new(path);
+    let (kind, scope, dir) = locate(project_path, path)?;
+
+    if !valid_name(new_name) {
+        return Err(format!("Name '{}' must use lowercase letters, digits, '-' or '_'", new_name));
+    }
+
+    let new_path = path.with_file_name(format!("{}.md", new_name));
+    if new_path.exists() {
+        return Err(format!("{} already exists", new_path.display()));
+    }
+
+    fs::rename(path, &new_path).map_err(|e| format!("Failed to rename {}: {}", path.display(), e))?;
+
+    // Agents are named in their frontmatter too
+    if kind == CustomKind::Agent {
+        let content = fs::read_to_string(&new_path).map_err(|e| e.to_string())?;
+        if let Ok((frontmatter, _)) = parse_frontmatter(&content) {
+            let current = read_file(kind, scope, &dir, &new_path);
+            write_atomic(&new_path, &render(kind, new_name, &current.definition, &frontmatter))?;
+        }
+    }
+
+    Ok(new_path)
+}
+
+/// Delete a slash command or subagent
+pub fn delete(project_path: &str, path: &str) -> Result<(), String> {
+    let path = Path::new(path);
+    locate(project_path, path)?;
+
+    fs::remove_file(path).map_err(|e| format!("Failed to delete {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frontmatter_round_trip() {
+        let content = "---\n\
+                       description: Review a PR: style and tests\n\
+                       allowed-tools: Bash(git diff:*), Bash(gh pr view:*)\n\
+                       disable-model-invocation: true\n\
+                       ---\n\n\
+                       Review PR $ARGUMENTS";
+
+        let (frontmatter, body) = parse_frontmatter(content).unwrap();
+        let mut definition = to_definition(CustomKind::Command, &frontmatter, body);
+        assert_eq!(definition.description.as_deref(), Some("Review a PR: style and tests"));
+        assert_eq!(definition.allowed_tools, ["Bash(git diff:*)", "Bash(gh pr view:*)"]);
+        assert_eq!(definition.body, "Review PR $ARGUMENTS");
+
+        definition.model = Some("sonnet".to_string());
+        let rendered = render(CustomKind::Command, "review", &definition, &frontmatter);
+        assert!(rendered.contains("description: \"Review a PR: style and tests\"\n"));
+        assert!(rendered.contains("disable-model-invocation: true\n"));
+
+        let (frontmatter, _) = parse_frontmatter(&rendered).unwrap();
+        assert_eq!(to_definition(CustomKind::Command, &frontmatter, String::new()).model.as_deref(), Some("sonnet"));
+    }
+
+    #[test]
+    fn test_agent_tools_list_and_validation() {
+        let content = "---\nname: reviewer\ntools:\n  - Read\n  - Grep\n---\nYou review code.";
+        let (frontmatter, body) = parse_frontmatter(content).unwrap();
+        let definition = to_definition(CustomKind::Agent, &frontmatter, body);
+
+        assert_eq!(definition.allowed_tools, ["Read", "Grep"]);
+        assert_eq!(validate(CustomKind::Agent, "reviewer", &definition).len(), 1);
+        assert!(parse_frontmatter("---\ndescription: x\n").is_err());
+
+        assert_eq!(
+            invoked_command("<command-message>review is running…</command-message>\n<command-name>/frontend:review</command-name>"),
+            Some("frontend:review")
+        );
+    }
+}
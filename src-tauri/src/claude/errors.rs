@@ -0,0 +1,321 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::sessions::{truncate_string, ClaudeMessage, ContentBlock};
+
+/// Number of identical failing tool calls in a session that counts as a loop
+const REPEATED_FAILURE_THRESHOLD: u32 = 3;
+
+/// What went wrong in a session
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    ApiError,
+    ToolError,
+    BashExit,
+    RepeatedFailure,
+}
+
+impl ErrorKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::ApiError => "api_error",
+            ErrorKind::ToolError => "tool_error",
+            ErrorKind::BashExit => "bash_exit",
+            ErrorKind::RepeatedFailure => "repeated_failure",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "api_error" => Some(ErrorKind::ApiError),
+            "tool_error" => Some(ErrorKind::ToolError),
+            "bash_exit" => Some(ErrorKind::BashExit),
+            "repeated_failure" => Some(ErrorKind::RepeatedFailure),
+            _ => None,
+        }
+    }
+}
+
+/// An error detected in a session message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionError {
+    pub message_uuid: String,
+    pub session_id: String,
+    pub kind: ErrorKind,
+    pub detail: String,
+    pub tool_use_id: Option<String>,
+    pub timestamp: String,
+}
+
+/// Exit code reported in a Bash tool result, e.g. `Exit code 1`
+fn bash_exit_code(content: &str) -> Option<i32> {
+    content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Exit code "))
+        .and_then(|code| code.trim().parse().ok())
+}
+
+/// Decide whether a tool result is a failure, and describe it
+fn classify_tool_result(
+    tool_name: Option<&str>,
+    tool_input: Option<&str>,
+    content: &str,
+    is_error: bool,
+) -> Option<(ErrorKind, String)> {
+    if tool_name == Some("Bash") {
+        if let Some(code) = bash_exit_code(content).filter(|c| *c != 0) {
+            let command = tool_input
+                .and_then(|input| serde_json::from_str::<serde_json::Value>(input).ok())
+                .and_then(|input| input.get("command")?.as_str().map(String::from))
+                .unwrap_or_default();
+            return Some((
+                ErrorKind::BashExit,
+                format!("`{}` exited with code {}", truncate_string(&command, 60), code),
+            ));
+        }
+    }
+
+    if is_error {
+        let first_line = content.lines().next().unwrap_or_default();
+        return Some((
+            ErrorKind::ToolError,
+            format!(
+                "{} failed: {}",
+                tool_name.unwrap_or("Tool"),
+                truncate_string(first_line, 100)
+            ),
+        ));
+    }
+
+    None
+}
+
+fn record_error(conn: &Connection, error: &SessionError, file_path: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO session_errors (message_uuid, session_id, kind, detail, tool_use_id, timestamp, file_path)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            &error.message_uuid,
+            &error.session_id,
+            error.kind.as_str(),
+            &error.detail,
+            &error.tool_use_id,
+            &error.timestamp,
+            file_path,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Record the tool calls of a freshly indexed message and its errors
+///
+/// Tool results are matched to their calls through the `tool_calls` table, so
+/// calls and results may arrive in different reads of the file.
+pub fn scan_message(conn: &Connection, msg: &ClaudeMessage, file_path: &str) -> Result<(), String> {
+    let mut errors = vec![];
+    let error = |kind, detail, tool_use_id: Option<&str>| SessionError {
+        message_uuid: msg.uuid.clone(),
+        session_id: msg.session_id.clone(),
+        kind,
+        detail,
+        tool_use_id: tool_use_id.map(String::from),
+        timestamp: msg.timestamp.clone(),
+    };
+
+    if msg.is_api_error {
+        errors.push(error(
+            ErrorKind::ApiError,
+            truncate_string(&msg.content, 100),
+            None,
+        ));
+    }
+
+    for block in &msg.blocks {
+        match block {
+            ContentBlock::ToolUse { id, name, input } => {
+                conn.execute(
+                    "INSERT OR IGNORE INTO tool_calls (id, session_id, message_uuid, name, input, timestamp, file_path)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![id, &msg.session_id, &msg.uuid, name, input.to_string(), &msg.timestamp, file_path],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            ContentBlock::ToolResult {
+                tool_use_id,
+                content,
+                is_error,
+            } => {
+                let call: Option<(String, String)> = conn
+                    .query_row(
+                        "SELECT name, input FROM tool_calls WHERE session_id = ?1 AND id = ?2",
+                        params![&msg.session_id, tool_use_id],
+                        |row| Ok((row.get(0)?, row.get(1)?)),
+                    )
+                    .optional()
+                    .map_err(|e| e.to_string())?;
+
+                let failure = classify_tool_result(
+                    call.as_ref().map(|(name, _)| name.as_str()),
+                    call.as_ref().map(|(_, input)| input.as_str()),
+                    content,
+                    *is_error,
+                );
+
+                conn.execute(
+                    "UPDATE tool_calls SET is_error = ?1 WHERE session_id = ?2 AND id = ?3",
+                    params![failure.is_some(), &msg.session_id, tool_use_id],
+                )
+                .map_err(|e| e.to_string())?;
+
+                let (kind, detail) = match failure {
+                    Some(failure) => failure,
+                    None => continue,
+                };
+                errors.push(error(kind, detail, Some(tool_use_id)));
+
+                // Report a loop once, when the same failing call reaches the threshold
+                if let Some((name, input)) = &call {
+                    let failures: u32 = conn
+                        .query_row(
+                            "SELECT COUNT(*) FROM tool_calls
+                             WHERE session_id = ?1 AND name = ?2 AND input = ?3 AND is_error = 1",
+                            params![&msg.session_id, name, input],
+                            |row| row.get(0),
+                        )
+                        .map_err(|e| e.to_string())?;
+
+                    if failures == REPEATED_FAILURE_THRESHOLD {
+                        errors.push(error(
+                            ErrorKind::RepeatedFailure,
+                            format!("{} failed {} times with the same input", name, failures),
+                            Some(tool_use_id),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for error in &errors {
+        record_error(conn, error, file_path)?;
+    }
+
+    Ok(())
+}
+
+fn row_to_error(row: &rusqlite::Row) -> rusqlite::Result<Option<SessionError>> {
+    let kind: String = row.get(2)?;
+
+    // Kinds written by a newer version are skipped
+    Ok(match ErrorKind::parse(&kind) {
+        Some(kind) => Some(SessionError {
+            message_uuid: row.get(0)?,
+            session_id: row.get(1)?,
+            kind,
+            detail: row.get(3)?,
+            tool_use_id: row.get(4)?,
+            timestamp: row.get(5)?,
+        }),
+        None => None,
+    })
+}
+
+/// Errors detected in a session, in order
+pub fn get_session_errors(conn: &Connection, session_id: &str) -> Result<Vec<SessionError>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT message_uuid, session_id, kind, detail, tool_use_id, timestamp
+             FROM session_errors
+             WHERE session_id = ?1
+             ORDER BY timestamp, id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let errors = stmt
+        .query_map(params![session_id], row_to_error)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Ok(errors)
+}
+
+/// Errors in tracked projects not notified yet that happened at or after `since`, in order
+///
+/// Every pending error is marked as notified, so older ones found when a file
+/// is indexed late or re-indexed are dropped rather than reported, as are
+/// those of untracked projects indexed for stats.
+pub fn take_unnotified(conn: &Connection, since: &str) -> Result<Vec<SessionError>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT message_uuid, session_id, kind, detail, tool_use_id, timestamp
+             FROM session_errors
+             WHERE notified = 0 AND timestamp >= ?1
+               AND session_id IN (
+                   SELECT id FROM sessions WHERE project_path IN (SELECT path FROM projects)
+               )
+             ORDER BY timestamp, id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let errors = stmt
+        .query_map(params![since], row_to_error)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    conn.execute("UPDATE session_errors SET notified = 1 WHERE notified = 0", [])
+        .map_err(|e| e.to_string())?;
+
+    Ok(errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_exit_code() {
+        assert_eq!(bash_exit_code("Exit code 2\nerror: could not compile"), Some(2));
+        assert_eq!(bash_exit_code("all good"), None);
+
+        let failure = classify_tool_result(
+            Some("Bash"),
+            Some(r#"{"command":"cargo build"}"#),
+            "Exit code 101",
+            false,
+        );
+        assert_eq!(
+            failure,
+            Some((ErrorKind::BashExit, "`cargo build` exited with code 101".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_classify_tool_error() {
+        let failure = classify_tool_result(Some("Read"), None, "File does not exist.", true);
+        assert_eq!(failure.map(|(kind, _)| kind), Some(ErrorKind::ToolError));
+        assert_eq!(classify_tool_result(Some("Bash"), None, "Exit code 0", false), None);
+    }
+
+    #[test]
+    fn test_classify_multibyte_output() {
+        let output = format!("{}\nmore", "ファイルが見つかりません".repeat(20));
+        let (_, detail) = classify_tool_result(Some("Read"), None, &output, true).unwrap();
+        assert!(detail.ends_with("..."));
+
+        let input = serde_json::json!({ "command": format!("echo {}", "é".repeat(100)) }).to_string();
+        let (kind, _) = classify_tool_result(Some("Bash"), Some(&input), "Exit code 1", false).unwrap();
+        assert_eq!(kind, ErrorKind::BashExit);
+    }
+}
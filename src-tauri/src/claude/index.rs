@@ -0,0 +1,833 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::errors;
+use super::paths::{encode_project_path, find_claude_project_dir, list_claude_projects};
+use super::pricing::TokenUsage;
+use super::search;
+use super::sessions::{read_messages_at, read_session_file_from, truncate_string, ClaudeMessage, ClaudeSession};
+
+/// Keeps one row of each message copied into resumed or forked sessions, the first indexed
+///
+/// For queries over `messages` aliased as `m`, so usage is counted once.
+pub const FIRST_COPY: &str = "m.id IN (SELECT MIN(id) FROM messages GROUP BY uuid)";
+
+/// Indexed state of a single session JSONL file
+struct IndexedFile {
+    size: u64,
+    mtime: i64,
+    offset: u64,
+}
+
+/// Bring the index for a project up to date with its JSONL files on disk
+///
+/// Files that have not changed since the last refresh are skipped, appended
+/// files are only parsed from their last indexed offset, and files that
+/// shrank or were rewritten are re-parsed from the start.
+pub fn refresh_project(conn: &mut Connection, project_path: &str) -> Result<(), String> {
+    let claude_dir = find_claude_project_dir(project_path)
+        .ok_or_else(|| format!("No Claude data found for project: {}", project_path))?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut touched_sessions: HashSet<String> = HashSet::new();
+    let mut seen_files: HashSet<String> = HashSet::new();
+
+    if let Ok(entries) = fs::read_dir(&claude_dir) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().map(|e| e == "jsonl").unwrap_or(false) {
+                let file_path = path.to_string_lossy().to_string();
+                seen_files.insert(file_path.clone());
+                index_file(&tx, project_path, &path, &mut touched_sessions)?;
+            }
+        }
+    }
+
+    // Drop files that no longer exist on disk
+    let stale_files: Vec<String> = {
+        let mut stmt = tx
+            .prepare("SELECT path FROM session_files WHERE project_path = ?1")
+            .map_err(|e| e.to_string())?;
+        let paths = stmt
+            .query_map(params![project_path], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .filter(|p| !seen_files.contains(p))
+            .collect();
+        paths
+    };
+
+    for file_path in stale_files {
+        touched_sessions.extend(sessions_in_file(&tx, &file_path)?);
+        tx.execute("DELETE FROM session_files WHERE path = ?1", params![&file_path])
+            .map_err(|e| e.to_string())?;
+    }
+
+    for session_id in &touched_sessions {
+        rebuild_session_summary(&tx, session_id, project_path)?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Like `refresh_project`, but a project Claude has not run in yet has nothing to index
+pub fn refresh_project_if_present(conn: &mut Connection, project_path: &str) -> Result<(), String> {
+    if find_claude_project_dir(project_path).is_none() {
+        return Ok(());
+    }
+    refresh_project(conn, project_path)
+}
+
+/// Refresh the index for every tracked project
+///
+/// Projects without Claude data are skipped.
+pub fn refresh_tracked_projects(conn: &mut Connection) -> Result<(), String> {
+    let project_paths: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT path FROM projects")
+            .map_err(|e| e.to_string())?;
+
+        let paths = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        paths
+    };
+
+    for project_path in &project_paths {
+        let _ = refresh_project(conn, project_path);
+    }
+
+    Ok(())
+}
+
+/// Refresh the index for every Claude project directory, tracked or not
+///
+/// Tracked projects are indexed under their own path, others under the path
+/// decoded from their directory name.
+pub fn refresh_all_projects(conn: &mut Connection) -> Result<(), String> {
+    let tracked: HashMap<String, String> = {
+        let mut stmt = conn
+            .prepare("SELECT path FROM projects")
+            .map_err(|e| e.to_string())?;
+
+        let paths = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .map(|path| (encode_project_path(&path), path))
+            .collect();
+        paths
+    };
+
+    for (decoded_path, dir) in list_claude_projects() {
+        let dir_name = dir.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let project_path = tracked.get(dir_name).unwrap_or(&decoded_path);
+        let _ = refresh_project(conn, project_path);
+    }
+
+    Ok(())
+}
+
+/// Result of indexing a single session file
+pub struct FileUpdate {
+    /// Messages appended since the last read
+    pub messages: Vec<ClaudeMessage>,
+    /// Updated summaries of every session the file touched
+    pub updated: Vec<ClaudeSession>,
+}
+
+/// Index a single session file, resuming from its last indexed offset
+pub fn refresh_file(conn: &mut Connection, project_path: &str, path: &Path) -> Result<FileUpdate, String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut touched_sessions: HashSet<String> = HashSet::new();
+
+    let messages = index_file(&tx, project_path, path, &mut touched_sessions)?;
+
+    let mut updated = vec![];
+
+    for session_id in &touched_sessions {
+        rebuild_session_summary(&tx, session_id, project_path)?;
+
+        if let Some(session) = get_session(&tx, session_id)? {
+            updated.push(session);
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(FileUpdate { messages, updated })
+}
+
+/// Whether the first and last messages indexed from a file are still on the lines they were read from
+///
+/// Appending leaves them in place, while a rewrite, even one keeping the size,
+/// moves or replaces them.
+fn indexed_lines_intact(conn: &Connection, file_path: &str, path: &Path) -> Result<bool, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT line_offset, uuid FROM messages
+             WHERE file_path = ?1 AND line_offset IN (
+                 SELECT MIN(line_offset) FROM messages WHERE file_path = ?1
+                 UNION SELECT MAX(line_offset) FROM messages WHERE file_path = ?1
+             )",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let indexed = stmt
+        .query_map(params![file_path], |row| Ok((row.get::<_, u64>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let lines: Vec<(PathBuf, u64)> = indexed
+        .iter()
+        .map(|(offset, _)| (path.to_path_buf(), *offset))
+        .collect();
+    let found = read_messages_at(&lines)?;
+
+    Ok(indexed
+        .iter()
+        .zip(found)
+        .all(|((_, uuid), msg)| msg.is_some_and(|m| &m.uuid == uuid)))
+}
+
+/// Index new content of a single JSONL file, recording the sessions it touched
+///
+/// Returns the messages that were appended since the last refresh.
+fn index_file(
+    conn: &Connection,
+    project_path: &str,
+    path: &Path,
+    touched_sessions: &mut HashSet<String>,
+) -> Result<Vec<ClaudeMessage>, String> {
+    let file_path = path.to_string_lossy().to_string();
+    let metadata = match path.metadata() {
+        Ok(m) => m,
+        Err(_) => return Ok(vec![]),
+    };
+
+    let size = metadata.len();
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    let indexed = conn
+        .query_row(
+            "SELECT size, mtime, offset FROM session_files WHERE path = ?1",
+            params![&file_path],
+            |row| {
+                Ok(IndexedFile {
+                    size: row.get(0)?,
+                    mtime: row.get(1)?,
+                    offset: row.get(2)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let start_offset = match indexed {
+        Some(ref f) if f.size == size && f.mtime == mtime => return Ok(vec![]),
+        // File was truncated or rewritten, start over
+        Some(ref f) if size < f.offset || !indexed_lines_intact(conn, &file_path, path)? => {
+            touched_sessions.extend(sessions_in_file(conn, &file_path)?);
+            for table in ["messages", "tool_calls", "session_errors"] {
+                conn.execute(
+                    &format!("DELETE FROM {} WHERE file_path = ?1", table),
+                    params![&file_path],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            0
+        }
+        Some(ref f) => f.offset,
+        None => 0,
+    };
+
+    let (messages, new_offset) = read_session_file_from(path, start_offset)?;
+
+    conn.execute(
+        "INSERT INTO session_files (path, project_path, size, mtime, offset)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(path) DO UPDATE SET size = ?3, mtime = ?4, offset = ?5",
+        params![&file_path, project_path, size, mtime, new_offset],
+    )
+    .map_err(|e| e.to_string())?;
+
+    for (line_offset, msg) in &messages {
+        insert_message(conn, msg, &file_path, *line_offset)?;
+        touched_sessions.insert(msg.session_id.clone());
+    }
+
+    // Errors re-detected in lines indexed before the file was rewritten are not new
+    if let Some(f) = indexed.as_ref().filter(|f| start_offset < f.offset) {
+        conn.execute(
+            "UPDATE session_errors SET notified = 1
+             WHERE file_path = ?1 AND message_uuid IN
+                (SELECT uuid FROM messages WHERE file_path = ?1 AND line_offset < ?2)",
+            params![&file_path, f.offset],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // mtime changes alone still move last_activity forward
+    if indexed.is_some() {
+        touched_sessions.extend(sessions_in_file(conn, &file_path)?);
+    }
+
+    Ok(messages.into_iter().map(|(_, msg)| msg).collect())
+}
+
+/// Index a message and record the errors detected in it
+fn insert_message(
+    conn: &Connection,
+    msg: &ClaudeMessage,
+    file_path: &str,
+    line_offset: u64,
+) -> Result<(), String> {
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO messages
+         (uuid, session_id, parent_uuid, msg_type, content, model, input_tokens, output_tokens,
+          cache_read_input_tokens, cache_creation_input_tokens, service_tier, timestamp, file_path,
+          line_offset)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            &msg.uuid,
+            &msg.session_id,
+            &msg.parent_uuid,
+            &msg.msg_type,
+            &msg.content,
+            &msg.model,
+            msg.input_tokens,
+            msg.output_tokens,
+            msg.cache_read_input_tokens,
+            msg.cache_creation_input_tokens,
+            &msg.service_tier,
+            &msg.timestamp,
+            file_path,
+            line_offset,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Messages of the session already indexed from another file are ignored above.
+    // Resumed and forked sessions copy earlier messages under their own id, so
+    // those are indexed again for the new session.
+    if inserted == 0 {
+        return Ok(());
+    }
+
+    // `id` is the rowid alias, so this is the new message's id
+    search::index_message(conn, conn.last_insert_rowid(), msg)?;
+    errors::scan_message(conn, msg, file_path)
+}
+
+fn sessions_in_file(conn: &Connection, file_path: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT session_id FROM messages WHERE file_path = ?1")
+        .map_err(|e| e.to_string())?;
+
+    let ids = stmt
+        .query_map(params![file_path], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(ids)
+}
+
+/// Recompute the `sessions` row for a session from its indexed messages
+fn rebuild_session_summary(conn: &Connection, session_id: &str, project_path: &str) -> Result<(), String> {
+    let (message_count, started_at, usage) = conn
+        .query_row(
+            "SELECT COUNT(*),
+                    MIN(timestamp),
+                    COALESCE(SUM(input_tokens), 0),
+                    COALESCE(SUM(output_tokens), 0),
+                    COALESCE(SUM(cache_read_input_tokens), 0),
+                    COALESCE(SUM(cache_creation_input_tokens), 0)
+             FROM messages WHERE session_id = ?1",
+            params![session_id],
+            |row| {
+                Ok((
+                    row.get::<_, u32>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    TokenUsage {
+                        input_tokens: row.get(2)?,
+                        output_tokens: row.get(3)?,
+                        cache_read_input_tokens: row.get(4)?,
+                        cache_creation_input_tokens: row.get(5)?,
+                    },
+                ))
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    if message_count == 0 {
+        conn.execute("DELETE FROM sessions WHERE id = ?1", params![session_id])
+            .map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    let first_message = conn
+        .query_row(
+            "SELECT content FROM messages
+             WHERE session_id = ?1 AND msg_type = 'user'
+             ORDER BY timestamp LIMIT 1",
+            params![session_id],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .map(|c| truncate_string(&c, 100))
+        .unwrap_or_else(|| "No messages".to_string());
+
+    let model: Option<String> = conn
+        .query_row(
+            "SELECT model FROM messages
+             WHERE session_id = ?1 AND model IS NOT NULL
+             ORDER BY timestamp LIMIT 1",
+            params![session_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let service_tier: Option<String> = conn
+        .query_row(
+            "SELECT service_tier FROM messages
+             WHERE session_id = ?1 AND service_tier IS NOT NULL
+             ORDER BY timestamp DESC LIMIT 1",
+            params![session_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    // Use the latest file mtime as last activity (more accurate for active sessions)
+    let latest_mtime: Option<i64> = conn
+        .query_row(
+            "SELECT MAX(f.mtime) FROM session_files f
+             WHERE f.path IN (SELECT DISTINCT file_path FROM messages WHERE session_id = ?1)",
+            params![session_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let last_activity = match latest_mtime {
+        Some(secs) => chrono::DateTime::from_timestamp(secs, 0)
+            .unwrap_or_default()
+            .to_rfc3339(),
+        None => conn
+            .query_row(
+                "SELECT MAX(timestamp) FROM messages WHERE session_id = ?1",
+                params![session_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default(),
+    };
+
+    let error_count: u32 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM session_errors WHERE session_id = ?1",
+            params![session_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let project_name = project_path
+        .rsplit('/')
+        .next()
+        .unwrap_or(project_path)
+        .to_string();
+
+    conn.execute(
+        "INSERT INTO sessions
+         (id, project_path, project_name, first_message, message_count, total_tokens, model, started_at, last_activity,
+          input_tokens, output_tokens, cache_read_input_tokens, cache_creation_input_tokens, service_tier,
+          error_count)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+         ON CONFLICT(id) DO UPDATE SET
+            project_path = ?2, project_name = ?3, first_message = ?4, message_count = ?5,
+            total_tokens = ?6, model = ?7, started_at = ?8, last_activity = ?9,
+            input_tokens = ?10, output_tokens = ?11, cache_read_input_tokens = ?12,
+            cache_creation_input_tokens = ?13, service_tier = ?14, error_count = ?15",
+        params![
+            session_id,
+            project_path,
+            &project_name,
+            &first_message,
+            message_count,
+            usage.input_tokens
+                + usage.output_tokens
+                + usage.cache_read_input_tokens
+                + usage.cache_creation_input_tokens,
+            &model,
+            started_at.unwrap_or_default(),
+            &last_activity,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cache_read_input_tokens,
+            usage.cache_creation_input_tokens,
+            &service_tier,
+            error_count,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Get the files a session's messages were indexed from
+pub fn find_session_files(conn: &Connection, session_id: &str) -> Result<Vec<PathBuf>, String> {
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT file_path FROM messages WHERE session_id = ?1")
+        .map_err(|e| e.to_string())?;
+
+    let files = stmt
+        .query_map(params![session_id], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .map(PathBuf::from)
+        .collect();
+
+    Ok(files)
+}
+
+/// Where a page of a session's messages is stored, ordered by timestamp
+///
+/// Each entry is the file and line offset of a message with its uuid. Returns
+/// an empty page when the session has not been indexed.
+pub fn get_message_page(
+    conn: &Connection,
+    session_id: &str,
+    offset: u32,
+    limit: Option<u32>,
+) -> Result<Vec<(PathBuf, u64, String)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT file_path, line_offset, uuid FROM messages
+             WHERE session_id = ?1
+             ORDER BY timestamp, id
+             LIMIT ?2 OFFSET ?3",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let page = stmt
+        .query_map(params![session_id, limit.map(i64::from).unwrap_or(-1), offset], |row| {
+            Ok((PathBuf::from(row.get::<_, String>(0)?), row.get(1)?, row.get(2)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(page)
+}
+
+/// Whether any message of a session has been indexed
+pub fn is_session_indexed(conn: &Connection, session_id: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM messages WHERE session_id = ?1)",
+        params![session_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn row_to_session(row: &rusqlite::Row) -> rusqlite::Result<ClaudeSession> {
+    let input_tokens: u64 = row.get(9)?;
+    let cache_read_input_tokens: u64 = row.get(11)?;
+    let cache_creation_input_tokens: u64 = row.get(12)?;
+
+    let total_input = input_tokens + cache_read_input_tokens + cache_creation_input_tokens;
+    let cache_hit_ratio = if total_input > 0 {
+        cache_read_input_tokens as f64 / total_input as f64
+    } else {
+        0.0
+    };
+
+    Ok(ClaudeSession {
+        id: row.get(0)?,
+        project_path: row.get(1)?,
+        project_name: row.get(2)?,
+        first_message: row.get(3)?,
+        message_count: row.get(4)?,
+        total_tokens: row.get(5)?,
+        input_tokens,
+        output_tokens: row.get(10)?,
+        cache_read_input_tokens,
+        cache_creation_input_tokens,
+        cache_hit_ratio,
+        error_count: row.get(14)?,
+        service_tier: row.get(13)?,
+        model: row.get(6)?,
+        started_at: row.get(7)?,
+        last_activity: row.get(8)?,
+    })
+}
+
+const SESSION_COLUMNS: &str =
+    "id, project_path, project_name, first_message, message_count, total_tokens, model, started_at, last_activity,
+     input_tokens, output_tokens, cache_read_input_tokens, cache_creation_input_tokens, service_tier,
+     error_count";
+
+/// Get a single indexed session
+pub fn get_session(conn: &Connection, session_id: &str) -> Result<Option<ClaudeSession>, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM sessions WHERE id = ?1", SESSION_COLUMNS),
+        params![session_id],
+        row_to_session,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Get indexed sessions for a project, most recent first
+pub fn get_project_sessions(conn: &Connection, project_path: &str) -> Result<Vec<ClaudeSession>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM sessions WHERE project_path = ?1 ORDER BY last_activity DESC",
+            SESSION_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let sessions = stmt
+        .query_map(params![project_path], row_to_session)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(sessions)
+}
+
+/// Get the most recent indexed sessions across all tracked projects
+pub fn get_recent_sessions(conn: &Connection, limit: u32) -> Result<Vec<ClaudeSession>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM sessions
+             WHERE project_path IN (SELECT path FROM projects)
+             ORDER BY last_activity DESC
+             LIMIT ?1",
+            SESSION_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let sessions = stmt
+        .query_map(params![limit], row_to_session)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn open_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_schema(&conn).unwrap();
+        conn
+    }
+
+    fn session_file(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tusker-index-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.jsonl");
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn append(path: &Path, text: &str) {
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    fn line(uuid: &str, session_id: &str, msg_type: &str, text: &str, minute: u32) -> String {
+        serde_json::json!({
+            "uuid": uuid,
+            "sessionId": session_id,
+            "type": msg_type,
+            "timestamp": format!("2026-03-01T10:{:02}:00Z", minute),
+            "message": {
+                "content": [{ "type": "text", "text": text }],
+                "usage": { "input_tokens": 10, "output_tokens": 5 }
+            }
+        })
+        .to_string()
+            + "\n"
+    }
+
+    fn indexed_offset(conn: &Connection, path: &Path) -> u64 {
+        conn.query_row(
+            "SELECT offset FROM session_files WHERE path = ?1",
+            params![path.to_string_lossy()],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_refresh_file_resumes_from_offset() {
+        let mut conn = open_db();
+        let path = session_file("resume");
+        let first = line("u1", "s1", "user", "Fix the build", 0);
+        let second = line("u2", "s1", "assistant", "On it", 1);
+        let third = line("u3", "s1", "user", "Thanks", 2);
+
+        // The third line is still being written
+        append(&path, &first);
+        append(&path, &second);
+        append(&path, &third[..10]);
+
+        let update = refresh_file(&mut conn, "/work/app", &path).unwrap();
+        assert_eq!(update.messages.len(), 2);
+        assert_eq!(indexed_offset(&conn, &path), (first.len() + second.len()) as u64);
+
+        append(&path, &third[10..]);
+        let update = refresh_file(&mut conn, "/work/app", &path).unwrap();
+        assert_eq!(update.messages.len(), 1);
+        assert_eq!(update.messages[0].uuid, "u3");
+
+        let session = get_session(&conn, "s1").unwrap().unwrap();
+        assert_eq!(session.message_count, 3);
+        assert_eq!(session.input_tokens, 30);
+        assert_eq!(session.first_message, "Fix the build");
+    }
+
+    #[test]
+    fn test_refresh_file_reindexes_truncated_file() {
+        let mut conn = open_db();
+        let path = session_file("truncate");
+        append(&path, &line("u1", "s1", "user", "First attempt", 0));
+        append(&path, &line("u2", "s1", "assistant", "Done", 1));
+        refresh_file(&mut conn, "/work/app", &path).unwrap();
+
+        fs::write(&path, line("u9", "s1", "user", "Rewritten", 5)).unwrap();
+        let update = refresh_file(&mut conn, "/work/app", &path).unwrap();
+
+        assert_eq!(update.messages.len(), 1);
+        let session = get_session(&conn, "s1").unwrap().unwrap();
+        assert_eq!(session.message_count, 1);
+        assert_eq!(session.first_message, "Rewritten");
+        assert_eq!(session.started_at, "2026-03-01T10:05:00Z");
+    }
+
+    #[test]
+    fn test_refresh_file_reindexes_same_size_rewrite() {
+        let mut conn = open_db();
+        let path = session_file("rewrite");
+        append(&path, &line("u1", "s1", "user", "First", 0));
+        append(&path, &line("u2", "s1", "assistant", "Done", 1));
+        refresh_file(&mut conn, "/work/app", &path).unwrap();
+
+        fs::write(&path, line("u3", "s1", "user", "Again", 0) + &line("u4", "s1", "assistant", "Okay", 1)).unwrap();
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(10);
+        fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+        let update = refresh_file(&mut conn, "/work/app", &path).unwrap();
+
+        let uuids: Vec<_> = update.messages.iter().map(|m| m.uuid.as_str()).collect();
+        assert_eq!(uuids, ["u3", "u4"]);
+        let session = get_session(&conn, "s1").unwrap().unwrap();
+        assert_eq!(session.message_count, 2);
+        assert_eq!(session.first_message, "Again");
+    }
+
+    #[test]
+    fn test_copied_messages_count_for_each_session() {
+        let mut conn = open_db();
+        let original = session_file("original");
+        let resumed = session_file("resumed");
+        append(&original, &line("u1", "s1", "user", "Add a cache", 0));
+        append(&resumed, &line("u1", "s2", "user", "Add a cache", 0));
+        append(&resumed, &line("u2", "s2", "user", "Now test it", 3));
+
+        refresh_file(&mut conn, "/work/app", &original).unwrap();
+        refresh_file(&mut conn, "/work/app", &resumed).unwrap();
+
+        assert_eq!(get_session(&conn, "s1").unwrap().unwrap().message_count, 1);
+        assert_eq!(get_session(&conn, "s2").unwrap().unwrap().message_count, 2);
+    }
+
+    #[test]
+    fn test_message_page_points_at_indexed_lines() {
+        let mut conn = open_db();
+        let path = session_file("page");
+        append(&path, &line("u2", "s1", "assistant", "Second", 1));
+        append(&path, &line("u1", "s1", "user", "First", 0));
+        append(&path, &line("u3", "s1", "user", "Third", 2));
+        refresh_file(&mut conn, "/work/app", &path).unwrap();
+
+        let page = get_message_page(&conn, "s1", 1, Some(5)).unwrap();
+        let lines: Vec<_> = page.iter().map(|(path, offset, _)| (path.clone(), *offset)).collect();
+        let messages = read_messages_at(&lines).unwrap();
+
+        let uuids: Vec<_> = messages.iter().flatten().map(|m| m.uuid.as_str()).collect();
+        assert_eq!(uuids, ["u2", "u3"]);
+        assert!(get_message_page(&conn, "s1", 3, None).unwrap().is_empty());
+        assert!(!is_session_indexed(&conn, "s2").unwrap());
+    }
+
+    #[test]
+    fn test_only_new_errors_are_unnotified() {
+        let mut conn = open_db();
+        conn.execute("INSERT INTO projects (id, name, path) VALUES ('p1', 'app', '/work/app')", [])
+            .unwrap();
+        let path = session_file("errors");
+        let api_error = |uuid: &str, session_id: &str, minute: u32| {
+            let text = line(uuid, session_id, "assistant", "Overloaded", minute);
+            let mut line: serde_json::Value = serde_json::from_str(&text).unwrap();
+            line["isApiErrorMessage"] = true.into();
+            line.to_string() + "\n"
+        };
+        append(&path, &api_error("u1", "s1", 0));
+        append(&path, &api_error("u2", "s1", 10));
+        refresh_file(&mut conn, "/work/app", &path).unwrap();
+
+        // Untracked projects are only indexed for stats
+        let untracked = session_file("errors-untracked");
+        append(&untracked, &api_error("u9", "s9", 10));
+        refresh_file(&mut conn, "/work/other", &untracked).unwrap();
+
+        let errors = errors::take_unnotified(&conn, "2026-03-01T10:05:00.000Z").unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message_uuid, "u2");
+
+        // Rewriting the file re-detects the error it kept, which is not new
+        fs::write(&path, api_error("u2", "s1", 10)).unwrap();
+        refresh_file(&mut conn, "/work/app", &path).unwrap();
+        append(&path, &api_error("u3", "s1", 20));
+        refresh_file(&mut conn, "/work/app", &path).unwrap();
+
+        let errors = errors::take_unnotified(&conn, "2026-03-01T10:05:00.000Z").unwrap();
+        let uuids: Vec<_> = errors.iter().map(|e| e.message_uuid.as_str()).collect();
+        assert_eq!(uuids, ["u3"]);
+    }
+
+    #[test]
+    fn test_summary_dropped_with_its_last_file() {
+        let mut conn = open_db();
+        let path = session_file("dropped");
+        append(&path, &line("u1", "s1", "user", "Hello", 0));
+        refresh_file(&mut conn, "/work/app", &path).unwrap();
+
+        fs::write(&path, "").unwrap();
+        refresh_file(&mut conn, "/work/app", &path).unwrap();
+
+        assert!(get_session(&conn, "s1").unwrap().is_none());
+        assert!(find_session_files(&conn, "s1").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use super::memory::{self, parse_imports, resolve_import, MemoryScope};
+
+/// Sections shorter than this are too generic to report as duplicates
+const MIN_DUPLICATE_CHARS: usize = 80;
+
+/// Size of one memory file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryFileSize {
+    pub path: String,
+    pub name: String,
+    pub scope: MemoryScope,
+    pub tokens: u64,
+    pub lines: usize,
+    pub loaded_at_start: bool, // Nested files and their imports load only when Claude works there
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    FileTooLarge,
+    TotalTooLarge,
+    BrokenImport,
+    DuplicateSection,
+}
+
+/// A problem found in a project's memory files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintIssue {
+    pub kind: LintKind,
+    pub file: Option<String>, // Path of the file, when the issue is about one
+    pub message: String,
+}
+
+/// Sizes and problems of the memory Claude loads for a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryLintReport {
+    pub files: Vec<MemoryFileSize>,
+    pub total_tokens: u64, // Files loaded at startup only
+    pub file_token_limit: u32,
+    pub total_token_limit: u32,
+    pub issues: Vec<LintIssue>,
+}
+
+/// Rough token count, about four characters per token
+pub fn approx_tokens(content: &str) -> u64 {
+    (content.chars().count() as u64).div_ceil(4)
+}
+
+/// Whether an `@` word is meant as a file import rather than a mention
+fn looks_like_path(import: &str) -> bool {
+    import.contains('/') || import.contains('.')
+}
+
+/// Markdown sections as heading and body, ignoring `#` lines inside code blocks
+fn sections(content: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = vec![];
+    let mut in_fence = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+
+        let heading = line
+            .trim_start_matches('#')
+            .strip_prefix(' ')
+            .filter(|_| !in_fence && line.starts_with('#'));
+
+        match (heading, sections.last_mut()) {
+            (Some(heading), _) => sections.push((heading.trim().to_string(), String::new())),
+            (None, Some((_, body))) => {
+                let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !line.is_empty() {
+                    body.push_str(&line);
+                    body.push('\n');
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    sections
+}
+
+/// Sections with the same body, ignoring whitespace, in more than one file
+fn duplicate_sections(files: &[(&str, &str)]) -> Vec<(String, Vec<String>)> {
+    let mut by_body: BTreeMap<String, (String, Vec<String>)> = BTreeMap::new();
+
+    for (name, content) in files {
+        for (heading, body) in sections(content) {
+            if body.len() < MIN_DUPLICATE_CHARS {
+                continue;
+            }
+
+            let (_, names) = by_body.entry(body).or_insert_with(|| (heading, vec![]));
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    by_body
+        .into_values()
+        .filter(|(_, names)| names.len() > 1)
+        .collect()
+}
+
+/// Measure a project's memory files and find oversized files, broken imports and duplication
+pub fn lint_project_memory(
+    project_path: &str,
+    file_token_limit: u32,
+    total_token_limit: u32,
+) -> MemoryLintReport {
+    let mut files = vec![];
+    let mut contents = vec![];
+    let mut issues = vec![];
+    let mut at_start = HashSet::new();
+
+    for file in memory::discover(project_path).into_iter().filter(|f| f.exists) {
+        let content = fs::read_to_string(&file.path).unwrap_or_default();
+        let tokens = approx_tokens(&content);
+
+        // Imports come after the file importing them, so the importer is already decided
+        let loaded_at_start = file.scope != MemoryScope::Nested
+            && file
+                .imported_from
+                .as_ref()
+                .is_none_or(|from| at_start.contains(from));
+        if loaded_at_start {
+            at_start.insert(file.path.clone());
+        }
+
+        if tokens > file_token_limit as u64 {
+            issues.push(LintIssue {
+                kind: LintKind::FileTooLarge,
+                file: Some(file.path.clone()),
+                message: format!(
+                    "{} is about {} tokens, over the {} token limit",
+                    file.name, tokens, file_token_limit
+                ),
+            });
+        }
+
+        for import in parse_imports(&content).into_iter().filter(|i| looks_like_path(i)) {
+            let resolved = resolve_import(&import, Path::new(&file.path));
+            if !resolved.is_some_and(|path| path.is_file()) {
+                issues.push(LintIssue {
+                    kind: LintKind::BrokenImport,
+                    file: Some(file.path.clone()),
+                    message: format!("@{} in {} does not point to a file", import, file.name),
+                });
+            }
+        }
+
+        files.push(MemoryFileSize {
+            path: file.path,
+            name: file.name.clone(),
+            scope: file.scope,
+            tokens,
+            lines: content.lines().count(),
+            loaded_at_start,
+        });
+        contents.push((file.name, content));
+    }
+
+    let total_tokens = files
+        .iter()
+        .filter(|f| f.loaded_at_start)
+        .map(|f| f.tokens)
+        .sum();
+
+    if total_tokens > total_token_limit as u64 {
+        issues.push(LintIssue {
+            kind: LintKind::TotalTooLarge,
+            file: None,
+            message: format!(
+                "Memory loaded at startup is about {} tokens, over the {} token limit",
+                total_tokens, total_token_limit
+            ),
+        });
+    }
+
+    let contents: Vec<(&str, &str)> = contents
+        .iter()
+        .map(|(name, content)| (name.as_str(), content.as_str()))
+        .collect();
+    for (heading, names) in duplicate_sections(&contents) {
+        issues.push(LintIssue {
+            kind: LintKind::DuplicateSection,
+            file: None,
+            message: format!("Section \"{}\" is repeated in {}", heading, names.join(", ")),
+        });
+    }
+
+    MemoryLintReport {
+        files,
+        total_tokens,
+        file_token_limit,
+        total_token_limit,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TESTING: &str = "Run `mix test` before every commit and keep the suite green on CI at all times.";
+
+    #[test]
+    fn test_duplicate_sections() {
+        let root = format!("# Project\nPhoenix app\n\n## Testing\n{}\n", TESTING);
+        let nested = format!("# Lib\n```sh\n# not a heading\n```\n### Tests\n  {}\n", TESTING);
+        let unrelated = "## Testing\nShort note\n";
+
+        let duplicates = duplicate_sections(&[
+            ("CLAUDE.md", root.as_str()),
+            ("lib/CLAUDE.md", nested.as_str()),
+            ("docs/notes.md", unrelated),
+        ]);
+
+        assert_eq!(
+            duplicates,
+            [("Testing".to_string(), vec!["CLAUDE.md".to_string(), "lib/CLAUDE.md".to_string()])]
+        );
+        assert_eq!(sections(&nested).len(), 2);
+    }
+
+    #[test]
+    fn test_approx_tokens() {
+        assert_eq!(approx_tokens(""), 0);
+        assert_eq!(approx_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn test_looks_like_path() {
+        assert!(looks_like_path("docs/arch.md"));
+        assert!(!looks_like_path("kasun"));
+    }
+}
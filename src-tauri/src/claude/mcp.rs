@@ -0,0 +1,542 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{mpsc, OnceLock};
+use std::time::Duration;
+
+use super::paths::{get_claude_json_path, get_project_mcp_path};
+use super::settings::{read_settings, write_settings, SettingsScope};
+
+/// Shown instead of secret env and header values
+const MASK: &str = "••••••••";
+
+/// Attempts at an edit before giving up on a file that keeps changing
+const EDIT_ATTEMPTS: usize = 3;
+
+/// Longest to wait for the login shell to report its PATH
+const LOGIN_SHELL_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Where an MCP server is configured
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpScope {
+    User,    // `mcpServers` in ~/.claude.json, for every project
+    Project, // `mcpServers` in the project's .mcp.json, shared with the team
+    Local,   // The project's entry in ~/.claude.json, personal
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransport {
+    #[default]
+    Stdio,
+    Sse,
+    Http,
+}
+
+/// How to reach an MCP server
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct McpServerConfig {
+    pub transport: McpTransport,
+    pub command: Option<String>, // stdio only
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    pub url: Option<String>, // sse and http only
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// A configured MCP server, with secrets masked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpServer {
+    pub name: String,
+    pub scope: McpScope,
+    pub source: String, // Path of the config file
+    pub config: McpServerConfig,
+    pub enabled: bool,
+    pub problems: Vec<String>,
+}
+
+/// Config file of a scope, and the keys leading to its `mcpServers` object
+fn location(scope: McpScope, project_path: &str) -> Result<(PathBuf, Vec<String>), String> {
+    let claude_json = || get_claude_json_path().ok_or_else(|| "Could not find home directory".to_string());
+
+    Ok(match scope {
+        McpScope::User => (claude_json()?, vec!["mcpServers".to_string()]),
+        McpScope::Local => (
+            claude_json()?,
+            vec!["projects".to_string(), project_path.to_string(), "mcpServers".to_string()],
+        ),
+        McpScope::Project => (get_project_mcp_path(project_path), vec!["mcpServers".to_string()]),
+    })
+}
+
+fn object_at<'a>(root: &'a Value, keys: &[String]) -> Option<&'a Map<String, Value>> {
+    keys.iter()
+        .try_fold(root, |value, key| value.get(key))?
+        .as_object()
+}
+
+/// The object at a key path, created when missing
+fn object_at_mut<'a>(root: &'a mut Value, keys: &[String]) -> Result<&'a mut Map<String, Value>, String> {
+    let mut object = root.as_object_mut().ok_or("Config is not a JSON object")?;
+
+    for key in keys {
+        object = object
+            .entry(key.clone())
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .ok_or_else(|| format!("\"{}\" in config is not an object", key))?;
+    }
+
+    Ok(object)
+}
+
+/// String list at `key` of an object, created when missing
+fn list_mut<'a>(object: &'a mut Map<String, Value>, key: &str) -> Result<&'a mut Vec<Value>, String> {
+    object
+        .entry(key)
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .ok_or_else(|| format!("\"{}\" in config is not a list", key))
+}
+
+fn contains(list: Option<&Value>, name: &str) -> bool {
+    list.and_then(|l| l.as_array())
+        .is_some_and(|l| l.iter().any(|v| v == name))
+}
+
+fn string_map(value: Option<&Value>) -> BTreeMap<String, String> {
+    value
+        .and_then(|v| v.as_object())
+        .map(|object| {
+            object
+                .iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_config(server: &Value) -> McpServerConfig {
+    let text = |key: &str| server.get(key).and_then(|v| v.as_str()).map(String::from);
+
+    let transport = match text("type").as_deref() {
+        Some("sse") => McpTransport::Sse,
+        Some("http") | Some("streamable-http") => McpTransport::Http,
+        Some(_) => McpTransport::Stdio,
+        None if server.get("url").is_some() => McpTransport::Http,
+        None => McpTransport::Stdio,
+    };
+
+    McpServerConfig {
+        transport,
+        command: text("command"),
+        args: server
+            .get("args")
+            .and_then(|v| v.as_array())
+            .map(|args| args.iter().filter_map(|a| a.as_str().map(String::from)).collect())
+            .unwrap_or_default(),
+        env: string_map(server.get("env")),
+        url: text("url"),
+        headers: string_map(server.get("headers")),
+    }
+}
+
+/// Write a config over an existing server entry, keeping keys Tusker doesn't edit
+fn merge_config(existing: Option<&Value>, config: &McpServerConfig) -> Value {
+    let mut server = existing
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default();
+    for key in ["type", "command", "args", "env", "url", "headers"] {
+        server.remove(key);
+    }
+
+    match config.transport {
+        McpTransport::Stdio => {
+            server.insert("type".to_string(), json!("stdio"));
+            server.insert("command".to_string(), json!(config.command));
+            server.insert("args".to_string(), json!(config.args));
+            if !config.env.is_empty() {
+                server.insert("env".to_string(), json!(config.env));
+            }
+        }
+        McpTransport::Sse | McpTransport::Http => {
+            let transport = if config.transport == McpTransport::Sse { "sse" } else { "http" };
+            server.insert("type".to_string(), json!(transport));
+            server.insert("url".to_string(), json!(config.url));
+            if !config.headers.is_empty() {
+                server.insert("headers".to_string(), json!(config.headers));
+            }
+        }
+    }
+
+    Value::Object(server)
+}
+
+fn is_env_reference(value: &str) -> bool {
+    value.starts_with("${") && value.ends_with('}') && value.matches("${").count() == 1
+}
+
+/// Hide a secret, keeping `${VAR}` references and the last characters of long values
+pub fn mask(value: &str) -> String {
+    if is_env_reference(value) || value.is_empty() {
+        return value.to_string();
+    }
+
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() > 12 {
+        format!("{}{}", MASK, chars[chars.len() - 4..].iter().collect::<String>())
+    } else {
+        MASK.to_string()
+    }
+}
+
+fn mask_all(values: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    values.iter().map(|(k, v)| (k.clone(), mask(v))).collect()
+}
+
+/// Put back secrets the frontend sent still masked
+fn unmask(values: &mut BTreeMap<String, String>, existing: &BTreeMap<String, String>) {
+    for (key, value) in values.iter_mut() {
+        if let Some(secret) = existing.get(key).filter(|secret| mask(secret) == *value) {
+            *value = secret.clone();
+        }
+    }
+}
+
+/// PATH of the user's login shell, read once
+///
+/// Claude runs from a terminal and gets this PATH, while the app started from
+/// the Dock or a launcher gets a minimal one without e.g. Homebrew or nvm.
+fn login_path() -> Option<&'static OsString> {
+    static LOGIN_PATH: OnceLock<Option<OsString>> = OnceLock::new();
+
+    LOGIN_PATH
+        .get_or_init(|| {
+            let shell = std::env::var_os("SHELL")?;
+            let (tx, rx) = mpsc::channel();
+
+            // A shell stuck in its profile must not hold up listing servers
+            std::thread::spawn(move || {
+                let output = Command::new(shell).args(["-lc", "printf %s \"$PATH\""]).output();
+                let _ = tx.send(output);
+            });
+
+            let output = rx.recv_timeout(LOGIN_SHELL_TIMEOUT).ok()?.ok()?;
+            let path = String::from_utf8(output.stdout).ok()?;
+            (output.status.success() && !path.is_empty()).then(|| OsString::from(path))
+        })
+        .as_ref()
+}
+
+/// Whether a command resolves to a file, directly or through the login shell's PATH
+fn on_path(command: &str) -> bool {
+    if command.contains(std::path::MAIN_SEPARATOR) {
+        return Path::new(command).is_file();
+    }
+
+    login_path()
+        .cloned()
+        .or_else(|| std::env::var_os("PATH"))
+        .is_some_and(|paths| found_in(command, &paths))
+}
+
+/// Whether a command names a file in one of the directories of a PATH value
+///
+/// On Windows the extensions in PATHEXT are tried too, as e.g. `npx` is `npx.cmd`.
+fn found_in(command: &str, paths: &OsStr) -> bool {
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .filter(|ext| !ext.is_empty())
+            .map(String::from)
+            .collect()
+    } else {
+        vec![]
+    };
+
+    std::env::split_paths(paths).any(|dir| {
+        dir.join(command).is_file()
+            || extensions
+                .iter()
+                .any(|ext| dir.join(format!("{}{}", command, ext)).is_file())
+    })
+}
+
+/// Apply an edit to a settings file, re-reading it right before writing
+///
+/// Claude rewrites ~/.claude.json constantly, so when the file changed while
+/// the edit was made, the edit is made again on the latest content.
+fn edit_settings(path: &Path, edit: impl Fn(&mut Value) -> Result<(), String>) -> Result<(), String> {
+    for _ in 0..EDIT_ATTEMPTS {
+        let before = fs::read(path).ok();
+        let mut root = read_settings(path)?;
+        edit(&mut root)?;
+
+        if fs::read(path).ok() == before {
+            return write_settings(path, &root);
+        }
+    }
+
+    Err(format!("{} kept changing, try again", path.display()))
+}
+
+fn validate(name: &str, config: &McpServerConfig) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Server name '{}' must use letters, digits, '-' or '_'", name));
+    }
+
+    match config.transport {
+        McpTransport::Stdio => {
+            if config.command.as_deref().is_none_or(|c| c.trim().is_empty()) {
+                return Err("stdio servers need a command".to_string());
+            }
+        }
+        McpTransport::Sse | McpTransport::Http => {
+            let url = config.url.as_deref().unwrap_or_default();
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err("Remote servers need an http:// or https:// URL".to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Problems Claude would hit starting a server
+fn problems(config: &McpServerConfig) -> Vec<String> {
+    match (&config.transport, &config.command) {
+        (McpTransport::Stdio, Some(command)) if !on_path(command) => {
+            vec![format!("`{}` was not found on PATH", command)]
+        }
+        _ => vec![],
+    }
+}
+
+/// Whether a server is switched on for the project
+///
+/// `.mcp.json` servers are toggled in settings files, the others in the
+/// project's `disabledMcpServers` in ~/.claude.json.
+fn is_enabled(scope: McpScope, name: &str, project_path: &str, claude_json: &Value) -> Result<bool, String> {
+    if scope == McpScope::Project {
+        for settings_scope in [SettingsScope::Local, SettingsScope::Project, SettingsScope::User] {
+            let settings = read_settings(&settings_scope.path(project_path)?)?;
+            if contains(settings.get("disabledMcpjsonServers"), name) {
+                return Ok(false);
+            }
+        }
+        return Ok(true);
+    }
+
+    let project = &claude_json["projects"][project_path];
+    Ok(!contains(project.get("disabledMcpServers"), name))
+}
+
+/// MCP servers configured for a project, highest precedence first
+pub fn list_servers(project_path: &str) -> Result<Vec<McpServer>, String> {
+    let claude_json = match get_claude_json_path() {
+        Some(path) => read_settings(&path)?,
+        None => json!({}),
+    };
+    let mut servers = vec![];
+
+    for scope in [McpScope::Local, McpScope::Project, McpScope::User] {
+        let (path, keys) = location(scope, project_path)?;
+        let root = if scope == McpScope::Project {
+            read_settings(&path)?
+        } else {
+            claude_json.clone()
+        };
+
+        for (name, server) in object_at(&root, &keys).into_iter().flatten() {
+            let config = parse_config(server);
+
+            servers.push(McpServer {
+                name: name.clone(),
+                scope,
+                source: path.to_string_lossy().to_string(),
+                enabled: is_enabled(scope, name, project_path, &claude_json)?,
+                problems: problems(&config),
+                config: McpServerConfig {
+                    env: mask_all(&config.env),
+                    headers: mask_all(&config.headers),
+                    ..config
+                },
+            });
+        }
+    }
+
+    Ok(servers)
+}
+
+/// Add a server to a scope
+pub fn add_server(project_path: &str, scope: McpScope, name: &str, config: &McpServerConfig) -> Result<(), String> {
+    validate(name, config)?;
+
+    let (path, keys) = location(scope, project_path)?;
+
+    edit_settings(&path, |root| {
+        let servers = object_at_mut(root, &keys)?;
+
+        if servers.contains_key(name) {
+            return Err(format!("MCP server '{}' already exists in {}", name, path.display()));
+        }
+        servers.insert(name.to_string(), merge_config(None, config));
+
+        Ok(())
+    })
+}
+
+/// Replace a server's config, keeping secrets that come back masked
+pub fn update_server(
+    project_path: &str,
+    scope: McpScope,
+    name: &str,
+    config: &McpServerConfig,
+) -> Result<(), String> {
+    validate(name, config)?;
+
+    let (path, keys) = location(scope, project_path)?;
+
+    edit_settings(&path, |root| {
+        let servers = object_at_mut(root, &keys)?;
+
+        let existing = servers
+            .get(name)
+            .ok_or_else(|| format!("MCP server '{}' not found in {}", name, path.display()))?;
+        let current = parse_config(existing);
+
+        let mut config = config.clone();
+        unmask(&mut config.env, &current.env);
+        unmask(&mut config.headers, &current.headers);
+
+        let server = merge_config(Some(existing), &config);
+        servers.insert(name.to_string(), server);
+
+        Ok(())
+    })
+}
+
+/// Remove a server from a scope
+pub fn remove_server(project_path: &str, scope: McpScope, name: &str) -> Result<(), String> {
+    let (path, keys) = location(scope, project_path)?;
+
+    edit_settings(&path, |root| {
+        if object_at_mut(root, &keys)?.shift_remove(name).is_none() {
+            return Err(format!("MCP server '{}' not found in {}", name, path.display()));
+        }
+
+        Ok(())
+    })
+}
+
+/// Switch a server on or off for the project
+pub fn set_server_enabled(project_path: &str, scope: McpScope, name: &str, enabled: bool) -> Result<(), String> {
+    let (path, keys, enabled_key, disabled_key) = if scope == McpScope::Project {
+        (
+            SettingsScope::Local.path(project_path)?,
+            vec![],
+            Some("enabledMcpjsonServers"),
+            "disabledMcpjsonServers",
+        )
+    } else {
+        let (path, _) = location(scope, project_path)?;
+        (
+            path,
+            vec!["projects".to_string(), project_path.to_string()],
+            None,
+            "disabledMcpServers",
+        )
+    };
+
+    let (add_to, remove_from) = if enabled {
+        (enabled_key, Some(disabled_key))
+    } else {
+        (Some(disabled_key), enabled_key)
+    };
+
+    edit_settings(&path, |root| {
+        let object = object_at_mut(root, &keys)?;
+
+        if let Some(key) = remove_from {
+            list_mut(object, key)?.retain(|v| v != name);
+        }
+        if let Some(key) = add_to {
+            let list = list_mut(object, key)?;
+            if !list.iter().any(|v| v == name) {
+                list.push(json!(name));
+            }
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masked_secrets_survive_edits() {
+        let stored = json!({
+            "command": "npx",
+            "args": ["-y", "@modelcontextprotocol/server-github"],
+            "env": { "GITHUB_TOKEN": "ghp_abcdefghijklmnop1234", "ORG": "${GITHUB_ORG}" },
+            "timeout": 30000
+        });
+        let current = parse_config(&stored);
+        assert_eq!(current.transport, McpTransport::Stdio);
+
+        let mut edited = McpServerConfig {
+            env: mask_all(&current.env),
+            ..current.clone()
+        };
+        assert_eq!(edited.env["GITHUB_TOKEN"], "••••••••1234");
+        assert_eq!(edited.env["ORG"], "${GITHUB_ORG}");
+
+        edited.args.push("--read-only".to_string());
+        unmask(&mut edited.env, &current.env);
+        let merged = merge_config(Some(&stored), &edited);
+
+        assert_eq!(merged["env"]["GITHUB_TOKEN"], "ghp_abcdefghijklmnop1234");
+        assert_eq!(merged["timeout"], 30000);
+        assert_eq!(merged["type"], "stdio");
+        assert_eq!(merged["args"][2], "--read-only");
+    }
+
+    #[test]
+    fn test_validate() {
+        let remote = McpServerConfig {
+            transport: McpTransport::Http,
+            url: Some("https://mcp.example.com/mcp".to_string()),
+            ..Default::default()
+        };
+        assert!(validate("docs", &remote).is_ok());
+        assert!(validate("my docs", &remote).is_err());
+        assert!(validate("docs", &McpServerConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_found_in_path() {
+        let dir = std::env::temp_dir().join(format!("tusker-mcp-path-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // Found as is, or through PATHEXT on Windows
+        fs::write(dir.join(format!("fake-mcp-server{}", std::env::consts::EXE_SUFFIX)), "").unwrap();
+
+        let paths = std::env::join_paths([Path::new("/nonexistent"), &dir]).unwrap();
+        assert!(found_in("fake-mcp-server", &paths));
+        assert!(!found_in("other-mcp-server", &paths));
+        assert!(!found_in("fake-mcp-server", OsStr::new("")));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,485 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::paths::{get_claude_home, write_atomic};
+
+/// Directories never searched for nested CLAUDE.md files
+const SKIPPED_DIRS: [&str; 7] = ["node_modules", "target", "dist", "build", "vendor", "_build", "deps"];
+
+/// How deep below the project root nested CLAUDE.md files are searched for
+const MAX_NESTED_DEPTH: usize = 6;
+
+/// How many `@import` hops Claude follows
+const MAX_IMPORT_DEPTH: usize = 5;
+
+/// Largest LCS table a diff builds, about 16 MB
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Where a memory file comes from in Claude's lookup
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryScope {
+    User,    // ~/.claude/CLAUDE.md
+    Parent,  // CLAUDE.md in a directory above the project
+    Project, // CLAUDE.md or .claude/CLAUDE.md at the project root
+    Local,   // CLAUDE.local.md at the project root
+    Nested,  // CLAUDE.md in a subdirectory, loaded when Claude works there
+    Import,  // Pulled in by an `@path` import
+}
+
+/// A CLAUDE.md or memory file relevant to a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryFile {
+    pub path: String,
+    pub name: String, // Path relative to the project, or `~/`-relative outside it
+    pub scope: MemoryScope,
+    pub exists: bool, // The user and project CLAUDE.md are listed even before they exist
+    pub size: u64,
+    pub modified: Option<String>,
+    pub imported_from: Option<String>,
+}
+
+/// A saved revision of a memory file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryVersion {
+    pub id: i64,
+    pub path: String,
+    pub source: String, // "save", "restore", or "external" for edits made outside Tusker
+    pub size: usize,
+    pub saved_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Same,
+    Added,
+    Removed,
+}
+
+/// A line of a diff between two revisions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+fn display_name(path: &Path, project_path: &Path) -> String {
+    if let Ok(relative) = path.strip_prefix(project_path) {
+        return relative.to_string_lossy().to_string();
+    }
+    if let Some(relative) = dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        return format!("~/{}", relative.to_string_lossy());
+    }
+    path.to_string_lossy().to_string()
+}
+
+fn memory_file(
+    path: &Path,
+    project_path: &Path,
+    scope: MemoryScope,
+    imported_from: Option<&Path>,
+) -> MemoryFile {
+    let metadata = fs::metadata(path).ok().filter(|m| m.is_file());
+
+    MemoryFile {
+        path: path.to_string_lossy().to_string(),
+        name: display_name(path, project_path),
+        scope,
+        exists: metadata.is_some(),
+        size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+        modified: metadata
+            .and_then(|m| m.modified().ok())
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+        imported_from: imported_from.map(|p| p.to_string_lossy().to_string()),
+    }
+}
+
+/// Paths referenced by `@path` imports, outside code spans and fenced blocks
+pub fn parse_imports(content: &str) -> Vec<String> {
+    let mut imports = vec![];
+    let mut in_fence = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        // Drop inline code spans, which sit between odd and even backticks
+        let outside_code: String = line
+            .split('`')
+            .step_by(2)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        imports.extend(
+            outside_code
+                .split_whitespace()
+                .filter_map(|word| word.strip_prefix('@'))
+                .map(|path| path.trim_end_matches(['.', ',', ';', ':', ')', '!', '?']))
+                .filter(|path| !path.is_empty())
+                .map(String::from),
+        );
+    }
+
+    imports
+}
+
+/// Drop `.` and resolve `..` without touching the filesystem, so each file is listed once
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Absolute path of an import, relative to the file that imports it
+pub fn resolve_import(import: &str, from: &Path) -> Option<PathBuf> {
+    let path = if let Some(rest) = import.strip_prefix("~/") {
+        dirs::home_dir()?.join(rest)
+    } else if Path::new(import).is_absolute() {
+        PathBuf::from(import)
+    } else {
+        from.parent()?.join(import)
+    };
+
+    Some(normalize(&path))
+}
+
+fn find_nested(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    if depth > MAX_NESTED_DEPTH {
+        return;
+    }
+
+    let mut entries: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+        Err(_) => return,
+    };
+    entries.sort();
+
+    for path in entries.into_iter().filter(|p| p.is_dir()) {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if name.starts_with('.') || SKIPPED_DIRS.contains(&name) {
+            continue;
+        }
+
+        let claude_md = path.join("CLAUDE.md");
+        if claude_md.is_file() {
+            found.push(claude_md);
+        }
+        find_nested(&path, depth + 1, found);
+    }
+}
+
+/// Every CLAUDE.md and memory file Claude would load for a project, in load order
+pub fn discover(project_path: &str) -> Vec<MemoryFile> {
+    let root = Path::new(project_path);
+    let mut files = vec![];
+    let mut seen = HashSet::new();
+
+    let mut add = |files: &mut Vec<MemoryFile>, path: PathBuf, scope, from: Option<&Path>, always: bool| {
+        if (always || path.is_file()) && seen.insert(path.clone()) {
+            files.push(memory_file(&path, root, scope, from));
+        }
+    };
+
+    if let Some(claude_home) = get_claude_home() {
+        add(&mut files, claude_home.join("CLAUDE.md"), MemoryScope::User, None, true);
+    }
+
+    let mut parents: Vec<&Path> = root.ancestors().skip(1).collect();
+    parents.reverse();
+    for dir in parents {
+        add(&mut files, dir.join("CLAUDE.md"), MemoryScope::Parent, None, false);
+    }
+
+    add(&mut files, root.join("CLAUDE.md"), MemoryScope::Project, None, true);
+    add(&mut files, root.join(".claude").join("CLAUDE.md"), MemoryScope::Project, None, false);
+    add(&mut files, root.join("CLAUDE.local.md"), MemoryScope::Local, None, false);
+
+    let mut nested = vec![];
+    find_nested(root, 1, &mut nested);
+    for path in nested {
+        add(&mut files, path, MemoryScope::Nested, None, false);
+    }
+
+    // Follow imports breadth first, one hop per round
+    let mut frontier: Vec<PathBuf> = files
+        .iter()
+        .filter(|f| f.exists)
+        .map(|f| PathBuf::from(&f.path))
+        .collect();
+
+    for _ in 0..MAX_IMPORT_DEPTH {
+        let mut next = vec![];
+
+        for from in &frontier {
+            let content = fs::read_to_string(from).unwrap_or_default();
+            for import in parse_imports(&content) {
+                let path = match resolve_import(&import, from) {
+                    Some(path) if path.is_file() => path,
+                    _ => continue,
+                };
+                let before = files.len();
+                add(&mut files, path.clone(), MemoryScope::Import, Some(from), false);
+                if files.len() > before {
+                    next.push(path);
+                }
+            }
+        }
+
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    files
+}
+
+/// The discovered memory file at a path, so commands only touch files Claude would load
+pub fn find(project_path: &str, path: &str) -> Result<MemoryFile, String> {
+    discover(project_path)
+        .into_iter()
+        .find(|f| f.path == path)
+        .ok_or_else(|| format!("{} is not a memory file of this project", path))
+}
+
+/// Read a memory file, empty when it doesn't exist yet
+pub fn read(file: &MemoryFile) -> Result<String, String> {
+    if !file.exists {
+        return Ok(String::new());
+    }
+
+    fs::read_to_string(&file.path).map_err(|e| format!("Failed to read {}: {}", file.path, e))
+}
+
+fn row_to_version(row: &rusqlite::Row) -> rusqlite::Result<MemoryVersion> {
+    Ok(MemoryVersion {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        source: row.get(2)?,
+        size: row.get::<_, i64>(3)? as usize,
+        saved_at: row.get(4)?,
+    })
+}
+
+fn latest_version(conn: &Connection, path: &str) -> Result<Option<(i64, String)>, String> {
+    conn.query_row(
+        "SELECT id, content FROM memory_versions WHERE path = ?1 ORDER BY id DESC LIMIT 1",
+        params![path],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+fn record_version(conn: &Connection, path: &str, content: &str, source: &str) -> Result<i64, String> {
+    conn.execute(
+        "INSERT INTO memory_versions (path, content, source, saved_at) VALUES (?1, ?2, ?3, ?4)",
+        params![path, content, source, chrono::Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+fn get_version(conn: &Connection, id: i64) -> Result<MemoryVersion, String> {
+    conn.query_row(
+        "SELECT id, path, source, length(CAST(content AS BLOB)), saved_at FROM memory_versions WHERE id = ?1",
+        params![id],
+        row_to_version,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Write a memory file and record the revision
+///
+/// Content changed outside Tusker since the last recorded revision is kept as
+/// an `external` revision first, so it can still be restored.
+pub fn save(conn: &Connection, file: &MemoryFile, content: &str, source: &str) -> Result<MemoryVersion, String> {
+    let mut latest = latest_version(conn, &file.path)?;
+
+    if file.exists {
+        let on_disk = read(file)?;
+        if latest.as_ref().map(|(_, c)| c) != Some(&on_disk) {
+            let id = record_version(conn, &file.path, &on_disk, "external")?;
+            latest = Some((id, on_disk));
+        }
+    }
+
+    write_atomic(Path::new(&file.path), content)?;
+
+    let id = match latest {
+        Some((id, latest)) if latest == content => id,
+        _ => record_version(conn, &file.path, content, source)?,
+    };
+
+    get_version(conn, id)
+}
+
+/// Revisions of a memory file, newest first
+pub fn list_versions(conn: &Connection, path: &str) -> Result<Vec<MemoryVersion>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, path, source, length(CAST(content AS BLOB)), saved_at
+             FROM memory_versions
+             WHERE path = ?1
+             ORDER BY id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let versions = stmt
+        .query_map(params![path], row_to_version)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(versions)
+}
+
+/// Path and content of a revision
+pub fn version_content(conn: &Connection, id: i64) -> Result<(String, String), String> {
+    conn.query_row(
+        "SELECT path, content FROM memory_versions WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Changes a revision made to the one before it
+pub fn diff_version(conn: &Connection, id: i64) -> Result<Vec<DiffLine>, String> {
+    let (path, content) = version_content(conn, id)?;
+
+    let previous: Option<String> = conn
+        .query_row(
+            "SELECT content FROM memory_versions WHERE path = ?1 AND id < ?2 ORDER BY id DESC LIMIT 1",
+            params![&path, id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    Ok(diff_lines(previous.as_deref().unwrap_or_default(), &content))
+}
+
+/// Line diff of two texts, from their longest common subsequence
+///
+/// When the differing middle is too large for the table, it is shown as
+/// removed and added whole.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let line = |op, text: &str| DiffLine {
+        op,
+        text: text.to_string(),
+    };
+
+    // Only the differing middle needs the quadratic table
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    let mut diff: Vec<DiffLine> = old[..prefix].iter().map(|l| line(DiffOp::Same, l)).collect();
+    if a.len().saturating_mul(b.len()) > MAX_DIFF_CELLS {
+        diff.extend(a.iter().map(|l| line(DiffOp::Removed, l)));
+        diff.extend(b.iter().map(|l| line(DiffOp::Added, l)));
+        diff.extend(old[old.len() - suffix..].iter().map(|l| line(DiffOp::Same, l)));
+        return diff;
+    }
+
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            diff.push(line(DiffOp::Same, a[i]));
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            diff.push(line(DiffOp::Added, b[j]));
+            j += 1;
+        } else {
+            diff.push(line(DiffOp::Removed, a[i]));
+            i += 1;
+        }
+    }
+    diff.extend(old[old.len() - suffix..].iter().map(|l| line(DiffOp::Same, l)));
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_imports() {
+        let content = "See @docs/arch.md and @~/.claude/shared.md\n\
+                       Mention `@not/an/import.md` inline\n\
+                       ```\n@also/not.md\n```\n\
+                       Email me at kasun@example.com";
+
+        assert_eq!(parse_imports(content), ["docs/arch.md", "~/.claude/shared.md"]);
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("# Title\nold rule\nfooter", "# Title\nnew rule\nextra\nfooter");
+        let ops: Vec<DiffOp> = diff.iter().map(|l| l.op).collect();
+
+        assert_eq!(
+            ops,
+            [DiffOp::Same, DiffOp::Added, DiffOp::Added, DiffOp::Removed, DiffOp::Same]
+        );
+        assert_eq!(diff[3].text, "old rule");
+        assert!(diff_lines("a\nb", "a\nb").iter().all(|l| l.op == DiffOp::Same));
+
+        // Too large for the table, the middle is replaced whole
+        let old = (0..3000).map(|i| format!("old {}", i)).collect::<Vec<_>>().join("\n");
+        let new = (0..3000).map(|i| format!("new {}", i)).collect::<Vec<_>>().join("\n");
+        let diff = diff_lines(&format!("head\n{}\ntail", old), &format!("head\n{}\ntail", new));
+        assert_eq!(diff.len(), 6002);
+        assert_eq!(diff[1].op, DiffOp::Removed);
+        assert_eq!(diff[3001].op, DiffOp::Added);
+        assert_eq!(diff[6001].text, "tail");
+    }
+
+    #[test]
+    fn test_version_size_in_bytes() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_schema(&conn).unwrap();
+
+        let id = record_version(&conn, "/work/app/CLAUDE.md", "Größe ✓", "save").unwrap();
+        assert_eq!(get_version(&conn, id).unwrap().size, "Größe ✓".len());
+    }
+}
@@ -1,6 +1,21 @@
+pub mod aggregate;
+pub mod costs;
+pub mod custom;
+pub mod errors;
+pub mod index;
+pub mod lint;
+pub mod mcp;
+pub mod memory;
 pub mod paths;
+pub mod pricing;
+pub mod search;
 pub mod sessions;
+pub mod settings;
 pub mod stats;
+pub mod suggestions;
+pub mod task_sessions;
+pub mod timezone;
+pub mod todos;
 
 // Re-export main types for convenience
 pub use sessions::{ClaudeMessage, ClaudeSession};
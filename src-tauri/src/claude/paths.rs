@@ -1,4 +1,5 @@
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 /// Get the path to the ~/.claude directory
 pub fn get_claude_home() -> Option<PathBuf> {
@@ -47,11 +48,73 @@ pub fn get_history_path() -> Option<PathBuf> {
     get_claude_home().map(|h| h.join("history.jsonl"))
 }
 
+/// Get the path to ~/.claude/todos, where Claude saves each session's todo list
+pub fn get_todos_dir() -> Option<PathBuf> {
+    get_claude_home().map(|h| h.join("todos"))
+}
+
 /// Get path to settings.json
 pub fn get_settings_path() -> Option<PathBuf> {
     get_claude_home().map(|h| h.join("settings.json"))
 }
 
+/// Get path to ~/.claude.json, where Claude keeps user and per-project state
+pub fn get_claude_json_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".claude.json"))
+}
+
+/// Get path to a project's shared `.mcp.json`
+pub fn get_project_mcp_path(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path).join(".mcp.json")
+}
+
+/// Get path to a project's shared `.claude/settings.json`
+pub fn get_project_settings_path(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path).join(".claude").join("settings.json")
+}
+
+/// Get path to a project's personal `.claude/settings.local.json`
+pub fn get_project_local_settings_path(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path).join(".claude").join("settings.local.json")
+}
+
+/// Write a file through a temporary file, so Claude never reads a partial write
+///
+/// A symlinked file is written at its target, and an existing file keeps its
+/// permissions, e.g. 0600 on ~/.claude.json.
+pub fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let permissions = std::fs::metadata(&path).ok().map(|m| m.permissions());
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // Keep the content private until the original permissions are applied
+    #[cfg(unix)]
+    if permissions.is_some() {
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    }
+
+    options
+        .open(&tmp)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    if let Some(permissions) = permissions {
+        std::fs::set_permissions(&tmp, permissions)
+            .map_err(|e| format!("Failed to set permissions on {}: {}", tmp.display(), e))?;
+    }
+
+    std::fs::rename(&tmp, &path)
+        .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
 /// List all Claude project directories
 pub fn list_claude_projects() -> Vec<(String, PathBuf)> {
     let projects_dir = match get_claude_projects_dir() {
@@ -95,4 +158,25 @@ mod tests {
             "/Users/kasun/work/foo"
         );
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_keeps_mode_and_symlink() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("tusker-write-atomic-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("claude.json");
+        let link = dir.join("link.json");
+        std::fs::write(&target, "{}").unwrap();
+        std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o600)).unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_atomic(&link, "{\"a\":1}").unwrap();
+
+        assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "{\"a\":1}");
+        assert_eq!(std::fs::metadata(&target).unwrap().permissions().mode() & 0o777, 0o600);
+    }
 }
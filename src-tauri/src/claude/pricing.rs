@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Token counts that are billed separately
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+    }
+}
+
+/// Price of a model in USD per million tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_read: f64,
+    pub cache_creation: f64,
+}
+
+impl ModelPricing {
+    const fn new(input: f64, output: f64, cache_read: f64, cache_creation: f64) -> Self {
+        ModelPricing {
+            input,
+            output,
+            cache_read,
+            cache_creation,
+        }
+    }
+
+    /// Cost in USD of the given usage
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_read_input_tokens as f64 * self.cache_read
+            + usage.cache_creation_input_tokens as f64 * self.cache_creation)
+            / 1_000_000.0
+    }
+}
+
+/// Built-in prices keyed by model id prefix
+const BUILTIN_PRICING: &[(&str, ModelPricing)] = &[
+    ("claude-opus-4-5", ModelPricing::new(5.0, 25.0, 0.50, 6.25)),
+    ("claude-opus-4", ModelPricing::new(15.0, 75.0, 1.50, 18.75)),
+    ("claude-sonnet-4", ModelPricing::new(3.0, 15.0, 0.30, 3.75)),
+    ("claude-haiku-4", ModelPricing::new(1.0, 5.0, 0.10, 1.25)),
+    ("claude-3-opus", ModelPricing::new(15.0, 75.0, 1.50, 18.75)),
+    ("claude-3-7-sonnet", ModelPricing::new(3.0, 15.0, 0.30, 3.75)),
+    ("claude-3-5-sonnet", ModelPricing::new(3.0, 15.0, 0.30, 3.75)),
+    ("claude-3-5-haiku", ModelPricing::new(0.80, 4.0, 0.08, 1.0)),
+    ("claude-3-haiku", ModelPricing::new(0.25, 1.25, 0.03, 0.30)),
+];
+
+/// Model prices, matched against model ids by longest prefix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricingTable {
+    pub models: HashMap<String, ModelPricing>,
+}
+
+impl PricingTable {
+    pub fn builtin() -> Self {
+        PricingTable {
+            models: BUILTIN_PRICING
+                .iter()
+                .map(|(prefix, pricing)| (prefix.to_string(), pricing.clone()))
+                .collect(),
+        }
+    }
+
+    /// Built-in prices overridden by entries from a user pricing file
+    ///
+    /// The file maps model id prefixes to prices, e.g.
+    /// `{"claude-sonnet-4": {"input": 3, "output": 15, "cache_read": 0.3, "cache_creation": 3.75}}`.
+    pub fn load(override_path: &Path) -> Result<Self, String> {
+        let mut table = Self::builtin();
+
+        if override_path.exists() {
+            let content = fs::read_to_string(override_path)
+                .map_err(|e| format!("Failed to read pricing.json: {}", e))?;
+
+            let overrides: HashMap<String, ModelPricing> = serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse pricing.json: {}", e))?;
+
+            table.models.extend(overrides);
+        }
+
+        Ok(table)
+    }
+
+    /// Find the pricing for a model id such as `claude-sonnet-4-5-20250929`
+    pub fn for_model(&self, model: &str) -> Option<&ModelPricing> {
+        self.models
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, pricing)| pricing)
+    }
+
+    /// Cost in USD of usage on a model, zero for unknown models
+    pub fn cost(&self, model: &str, usage: &TokenUsage) -> f64 {
+        self.for_model(model)
+            .map(|pricing| pricing.cost(usage))
+            .unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let table = PricingTable::builtin();
+
+        assert_eq!(table.for_model("claude-opus-4-5-20251101").unwrap().input, 5.0);
+        assert_eq!(table.for_model("claude-opus-4-1-20250805").unwrap().input, 15.0);
+        assert!(table.for_model("<synthetic>").is_none());
+    }
+
+    #[test]
+    fn test_cost_per_million_tokens() {
+        let table = PricingTable::builtin();
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            cache_read_input_tokens: 1_000_000,
+            cache_creation_input_tokens: 1_000_000,
+        };
+
+        let cost = table.cost("claude-sonnet-4-5-20250929", &usage);
+        assert!((cost - (3.0 + 15.0 + 0.30 + 3.75)).abs() < 1e-9);
+    }
+}
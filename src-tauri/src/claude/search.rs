@@ -0,0 +1,160 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use super::index::get_session;
+use super::sessions::{ClaudeMessage, ClaudeSession, ContentBlock};
+use super::timezone::DayZone;
+
+/// Maximum number of sessions returned by a search
+const MAX_RESULTS: usize = 50;
+
+/// A session matching a search, with its best matching message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSearchResult {
+    pub session: ClaudeSession,
+    pub message_uuid: String,
+    pub snippet: String, // Matches wrapped in <mark></mark>
+    pub timestamp: String,
+}
+
+/// Text of a message that should be searchable: user and assistant text plus tool inputs
+fn searchable_text(msg: &ClaudeMessage) -> String {
+    let mut parts = vec![msg.content.clone()];
+
+    for block in &msg.blocks {
+        if let ContentBlock::ToolUse { name, input, .. } = block {
+            parts.push(name.clone());
+            collect_strings(input, &mut parts);
+        }
+    }
+
+    parts.retain(|p| !p.is_empty());
+    parts.join("\n")
+}
+
+/// Collect every string value in a tool input
+fn collect_strings(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => out.push(s.clone()),
+        Value::Array(arr) => arr.iter().for_each(|v| collect_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+/// Add a freshly indexed message to the full-text index
+///
+/// Search rows share the `id` of their row in the `messages` table, which keeps
+/// the two in sync through the delete trigger.
+pub fn index_message(conn: &Connection, message_id: i64, msg: &ClaudeMessage) -> Result<(), String> {
+    let body = searchable_text(msg);
+    if body.is_empty() {
+        return Ok(());
+    }
+
+    conn.execute(
+        "INSERT INTO messages_fts (rowid, body) VALUES (?1, ?2)",
+        params![message_id, &body],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Turn free text into an FTS5 query that matches all terms
+///
+/// Each term is quoted so punctuation in user input is not parsed as FTS syntax.
+fn to_fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Search indexed messages of tracked projects, returning the best match per session
+///
+/// Dates are local `YYYY-MM-DD` in `zone` and inclusive.
+pub fn search_sessions(
+    conn: &Connection,
+    query: &str,
+    project_path: Option<&str>,
+    date_from: Option<&str>,
+    date_to: Option<&str>,
+    zone: DayZone,
+) -> Result<Vec<SessionSearchResult>, String> {
+    let fts_query = to_fts_query(query);
+    if fts_query.is_empty() {
+        return Ok(vec![]);
+    }
+
+    zone.register_sql_functions(conn)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.session_id, m.uuid, m.timestamp,
+                    snippet(messages_fts, 0, '<mark>', '</mark>', '...', 16)
+             FROM messages_fts
+             JOIN messages m ON m.id = messages_fts.rowid
+             JOIN sessions s ON s.id = m.session_id
+             WHERE messages_fts MATCH ?1
+               AND s.project_path IN (SELECT path FROM projects)
+               AND (?2 IS NULL OR s.project_path = ?2)
+               AND (?3 IS NULL OR local_date(m.timestamp) >= ?3)
+               AND (?4 IS NULL OR local_date(m.timestamp) <= ?4)
+             ORDER BY bm25(messages_fts)",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![&fts_query, project_path, date_from, date_to], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut seen_sessions = HashSet::new();
+    let mut results = vec![];
+
+    for row in rows {
+        let (session_id, message_uuid, timestamp, snippet) = row.map_err(|e| e.to_string())?;
+
+        // Rows are ranked, so the first hit per session is its best match
+        if !seen_sessions.insert(session_id.clone()) {
+            continue;
+        }
+
+        if let Some(session) = get_session(conn, &session_id)? {
+            results.push(SessionSearchResult {
+                session,
+                message_uuid,
+                snippet,
+                timestamp,
+            });
+        }
+
+        if results.len() >= MAX_RESULTS {
+            break;
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_fts_query_quotes_terms() {
+        assert_eq!(to_fts_query("auth migration"), "\"auth\" \"migration\"");
+        assert_eq!(to_fts_query("say \"hi\" -v"), "\"say\" \"\"\"hi\"\"\" \"-v\"");
+        assert_eq!(to_fts_query("   "), "");
+    }
+}
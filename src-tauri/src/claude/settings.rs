@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::paths::{
+    get_project_local_settings_path, get_project_settings_path, get_settings_path, write_atomic,
+};
+
+/// Which settings file a rule lives in
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingsScope {
+    User,    // ~/.claude/settings.json
+    Project, // <project>/.claude/settings.json, shared with the team
+    Local,   // <project>/.claude/settings.local.json, personal
+}
+
+impl SettingsScope {
+    /// Scopes in the order Claude applies them, highest precedence first
+    const PRECEDENCE: [SettingsScope; 3] =
+        [SettingsScope::Local, SettingsScope::Project, SettingsScope::User];
+
+    pub fn path(&self, project_path: &str) -> Result<PathBuf, String> {
+        match self {
+            SettingsScope::User => {
+                get_settings_path().ok_or_else(|| "Could not find home directory".to_string())
+            }
+            SettingsScope::Project => Ok(get_project_settings_path(project_path)),
+            SettingsScope::Local => Ok(get_project_local_settings_path(project_path)),
+        }
+    }
+}
+
+/// What a permission rule does when it matches a tool call
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionBehavior {
+    Allow,
+    Deny,
+    Ask,
+}
+
+impl PermissionBehavior {
+    const ALL: [PermissionBehavior; 3] =
+        [PermissionBehavior::Allow, PermissionBehavior::Deny, PermissionBehavior::Ask];
+
+    fn key(&self) -> &'static str {
+        match self {
+            PermissionBehavior::Allow => "allow",
+            PermissionBehavior::Deny => "deny",
+            PermissionBehavior::Ask => "ask",
+        }
+    }
+}
+
+/// A permission rule and the settings file it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionRule {
+    pub rule: String, // e.g. "Bash(npm test:*)" or "WebFetch(domain:docs.rs)"
+    pub behavior: PermissionBehavior,
+    pub scope: SettingsScope,
+    pub source: String, // Path of the settings file
+}
+
+/// Read a settings file, treating a missing file as empty
+pub fn read_settings(path: &Path) -> Result<Value, String> {
+    if !path.exists() {
+        return Ok(json!({}));
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let settings: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+    if !settings.is_object() {
+        return Err(format!("{} is not a JSON object", path.display()));
+    }
+
+    Ok(settings)
+}
+
+/// Write a settings file, creating its directory
+pub fn write_settings(path: &Path, settings: &Value) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    write_atomic(path, &content)
+}
+
+/// Split `Tool(specifier)` into the tool name and specifier
+pub fn split_rule(rule: &str) -> Result<(&str, Option<&str>), String> {
+    match rule.split_once('(') {
+        Some((tool, rest)) => {
+            let specifier = rest
+                .strip_suffix(')')
+                .ok_or_else(|| format!("Rule '{}' is missing a closing ')'", rule))?;
+            Ok((tool, Some(specifier)))
+        }
+        None => Ok((rule, None)),
+    }
+}
+
+/// Check a rule is `Tool` or `Tool(specifier)`
+pub fn validate_rule(rule: &str) -> Result<(), String> {
+    let (tool, specifier) = split_rule(rule)?;
+
+    let valid_tool = tool.starts_with(|c: char| c.is_ascii_alphabetic())
+        && tool.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid_tool {
+        return Err(format!(
+            "Rule '{}' must start with a tool name like Bash, Edit or mcp__server__tool",
+            rule
+        ));
+    }
+
+    if let Some(specifier) = specifier {
+        if specifier.trim().is_empty() {
+            return Err(format!("Rule '{}' has an empty specifier", rule));
+        }
+        if specifier.contains('\n') {
+            return Err(format!("Rule '{}' must be on a single line", rule));
+        }
+    }
+
+    Ok(())
+}
+
+fn rule_list(settings: &Value, behavior: PermissionBehavior) -> impl Iterator<Item = &str> {
+    settings["permissions"][behavior.key()]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|rule| rule.as_str())
+}
+
+fn rule_list_mut(settings: &mut Value, behavior: PermissionBehavior) -> Result<&mut Vec<Value>, String> {
+    settings
+        .as_object_mut()
+        .ok_or("Settings are not a JSON object")?
+        .entry("permissions")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .ok_or("\"permissions\" in settings is not an object")?
+        .entry(behavior.key())
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .ok_or_else(|| format!("\"permissions.{}\" in settings is not an array", behavior.key()))
+}
+
+/// Add a rule to a settings value, returning whether it changed
+fn insert_rule(settings: &mut Value, behavior: PermissionBehavior, rule: &str) -> Result<bool, String> {
+    let rules = rule_list_mut(settings, behavior)?;
+    if rules.iter().any(|r| r == rule) {
+        return Ok(false);
+    }
+
+    rules.push(json!(rule));
+    Ok(true)
+}
+
+/// Remove a rule from a settings value, returning whether it was there
+fn delete_rule(settings: &mut Value, behavior: PermissionBehavior, rule: &str) -> Result<bool, String> {
+    let rules = rule_list_mut(settings, behavior)?;
+    let before = rules.len();
+    rules.retain(|r| r != rule);
+
+    Ok(rules.len() != before)
+}
+
+/// Permission rules from the user, project and local settings, highest precedence first
+pub fn get_permission_rules(project_path: &str) -> Result<Vec<PermissionRule>, String> {
+    let mut rules = vec![];
+
+    for scope in SettingsScope::PRECEDENCE {
+        let path = scope.path(project_path)?;
+        let settings = read_settings(&path)?;
+        let source = path.to_string_lossy().to_string();
+
+        for behavior in PermissionBehavior::ALL {
+            rules.extend(rule_list(&settings, behavior).map(|rule| PermissionRule {
+                rule: rule.to_string(),
+                behavior,
+                scope,
+                source: source.clone(),
+            }));
+        }
+    }
+
+    Ok(rules)
+}
+
+/// Add a permission rule to one of a project's settings files
+pub fn add_permission_rule(
+    project_path: &str,
+    scope: SettingsScope,
+    behavior: PermissionBehavior,
+    rule: &str,
+) -> Result<(), String> {
+    let rule = rule.trim();
+    validate_rule(rule)?;
+
+    let path = scope.path(project_path)?;
+    let mut settings = read_settings(&path)?;
+
+    if insert_rule(&mut settings, behavior, rule)? {
+        write_settings(&path, &settings)?;
+    }
+
+    Ok(())
+}
+
+/// Remove a permission rule from one of a project's settings files
+pub fn remove_permission_rule(
+    project_path: &str,
+    scope: SettingsScope,
+    behavior: PermissionBehavior,
+    rule: &str,
+) -> Result<(), String> {
+    let path = scope.path(project_path)?;
+    let mut settings = read_settings(&path)?;
+
+    if !delete_rule(&mut settings, behavior, rule)? {
+        return Err(format!("Rule '{}' not found in {}", rule, path.display()));
+    }
+
+    write_settings(&path, &settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rule() {
+        assert!(validate_rule("Bash(mix test:*)").is_ok());
+        assert!(validate_rule("WebFetch(domain:hexdocs.pm)").is_ok());
+        assert!(validate_rule("mcp__github__create_issue").is_ok());
+        assert!(validate_rule("Edit").is_ok());
+
+        assert!(validate_rule("Bash(npm test").is_err());
+        assert!(validate_rule("Bash()").is_err());
+        assert!(validate_rule("rm -rf /").is_err());
+        assert!(validate_rule("").is_err());
+    }
+
+    #[test]
+    fn test_rules_keep_unrelated_keys() {
+        let mut settings = json!({
+            "model": "opus",
+            "permissions": { "allow": ["Edit"], "defaultMode": "acceptEdits" }
+        });
+
+        assert!(insert_rule(&mut settings, PermissionBehavior::Deny, "Bash(rm -rf:*)").unwrap());
+        assert!(!insert_rule(&mut settings, PermissionBehavior::Allow, "Edit").unwrap());
+        assert!(delete_rule(&mut settings, PermissionBehavior::Allow, "Edit").unwrap());
+        assert!(!delete_rule(&mut settings, PermissionBehavior::Ask, "Edit").unwrap());
+
+        assert_eq!(settings["model"], "opus");
+        assert_eq!(settings["permissions"]["defaultMode"], "acceptEdits");
+        assert_eq!(settings["permissions"]["deny"][0], "Bash(rm -rf:*)");
+        assert_eq!(settings["permissions"]["allow"], json!([]));
+    }
+}
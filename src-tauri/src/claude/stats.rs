@@ -1,10 +1,25 @@
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 
+use super::aggregate::{compute_session_stats, format_timestamp, SessionStats};
 use super::paths::get_stats_cache_path;
+use super::pricing::{PricingTable, TokenUsage};
+use super::timezone::DayZone;
 
-/// Daily activity entry from stats-cache.json
+/// Where a day's stats came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StatsSource {
+    /// Claude's stats-cache.json
+    #[default]
+    Cache,
+    /// Aggregated from indexed session messages
+    Sessions,
+}
+
+/// Daily activity entry from stats-cache.json or the session files
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DailyActivity {
@@ -12,6 +27,8 @@ pub struct DailyActivity {
     pub message_count: u32,
     pub session_count: u32,
     pub tool_call_count: u32,
+    #[serde(default)]
+    pub source: StatsSource,
 }
 
 /// Daily token usage by model
@@ -23,7 +40,7 @@ pub struct DailyModelTokens {
 }
 
 /// Model usage statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelUsage {
     #[serde(default)]
@@ -51,7 +68,7 @@ pub struct LongestSession {
 }
 
 /// Raw stats-cache.json structure (for deserialization)
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct RawStatsCache {
     #[serde(default)]
@@ -83,18 +100,19 @@ pub struct ClaudeStats {
     pub daily_activity: Vec<DailyActivity>,
     pub model_usage: HashMap<String, ModelUsage>,
     pub longest_session: Option<LongestSession>,
+    pub hour_counts: HashMap<String, u32>,
     pub tokens_today: u64,
     pub messages_today: u32,
     pub sessions_today: u32,
 }
 
-/// Parse stats-cache.json and return structured stats
-pub fn get_claude_stats() -> Result<ClaudeStats, String> {
+/// Read stats-cache.json, if Claude has written one
+fn read_stats_cache() -> Result<Option<RawStatsCache>, String> {
     let stats_path = get_stats_cache_path()
         .ok_or_else(|| "Could not find Claude home directory".to_string())?;
 
     if !stats_path.exists() {
-        return Err("stats-cache.json not found".to_string());
+        return Ok(None);
     }
 
     let content = fs::read_to_string(&stats_path)
@@ -103,12 +121,94 @@ pub fn get_claude_stats() -> Result<ClaudeStats, String> {
     let raw: RawStatsCache = serde_json::from_str(&content)
         .map_err(|e| format!("Failed to parse stats-cache.json: {}", e))?;
 
-    // Get today's date
-    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    Ok(Some(raw))
+}
 
-    // Find today's activity
-    let today_activity = raw
+/// A cache timestamp in the format stats report, plain dates taken as midnight UTC
+fn normalize_timestamp(value: &str) -> Option<String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(format_timestamp(&time));
+    }
+
+    let midnight = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)?;
+    Some(format_timestamp(&midnight.and_utc()))
+}
+
+/// Build usage stats from stats-cache.json and the session index
+///
+/// Days up to the cache's last computed date come from the cache, later days
+/// (or every day when there is no cache) are aggregated from the indexed
+/// messages and bucketed by local date in `zone`.
+pub fn get_claude_stats(conn: &Connection, zone: DayZone) -> Result<ClaudeStats, String> {
+    let cache = read_stats_cache()?;
+
+    // Get today's local date
+    let today = zone.today();
+
+    // The cache covers every day up to its last computed date
+    let cached_until = cache.as_ref().and_then(|raw| {
+        raw.last_computed_date
+            .clone()
+            .or_else(|| raw.daily_activity.iter().map(|a| a.date.clone()).max())
+    });
+
+    let is_current = cache.is_some() && cached_until.as_deref() >= Some(today.as_str());
+    let live = if is_current {
+        SessionStats::default()
+    } else {
+        compute_session_stats(conn, cached_until.as_deref(), zone)?
+    };
+
+    let raw = cache.unwrap_or_default();
+    let cached = |date: &str| cached_until.as_deref().map(|c| date <= c).unwrap_or(false);
+
+    let mut daily_activity: Vec<DailyActivity> = raw
         .daily_activity
+        .into_iter()
+        .filter(|a| cached(&a.date))
+        .chain(live.daily_activity)
+        .collect();
+    daily_activity.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut daily_model_tokens: Vec<DailyModelTokens> = raw
+        .daily_model_tokens
+        .into_iter()
+        .filter(|d| cached(&d.date))
+        .collect();
+    daily_model_tokens.extend(
+        live.daily_model_tokens
+            .into_iter()
+            .map(|(date, tokens_by_model)| DailyModelTokens {
+                date,
+                tokens_by_model,
+            }),
+    );
+
+    let mut model_usage = raw.model_usage;
+    for (model, usage) in live.model_usage {
+        let total = model_usage.entry(model).or_default();
+        total.input_tokens += usage.input_tokens;
+        total.output_tokens += usage.output_tokens;
+        total.cache_read_input_tokens += usage.cache_read_input_tokens;
+        total.cache_creation_input_tokens += usage.cache_creation_input_tokens;
+    }
+
+    let mut hour_counts = raw.hour_counts;
+    for (hour, count) in live.hour_counts {
+        *hour_counts.entry(hour).or_default() += count;
+    }
+
+    let longest_session = [raw.longest_session, live.longest_session]
+        .into_iter()
+        .flatten()
+        .max_by_key(|s| s.duration)
+        .map(|s| LongestSession {
+            timestamp: normalize_timestamp(&s.timestamp).unwrap_or(s.timestamp),
+            ..s
+        });
+
+    // Find today's activity
+    let today_activity = daily_activity
         .iter()
         .find(|a| a.date == today);
 
@@ -117,27 +217,51 @@ pub fn get_claude_stats() -> Result<ClaudeStats, String> {
         .unwrap_or((0, 0));
 
     // Calculate today's tokens
-    let tokens_today: u64 = raw
-        .daily_model_tokens
+    let tokens_today: u64 = daily_model_tokens
         .iter()
         .find(|d| d.date == today)
         .map(|d| d.tokens_by_model.values().sum())
         .unwrap_or(0);
 
     Ok(ClaudeStats {
-        total_sessions: raw.total_sessions,
-        total_messages: raw.total_messages,
-        last_computed: raw.last_computed_date.unwrap_or_else(|| today.clone()),
-        first_session_date: raw.first_session_date,
-        daily_activity: raw.daily_activity,
-        model_usage: raw.model_usage,
-        longest_session: raw.longest_session,
+        total_sessions: raw.total_sessions + live.total_sessions,
+        total_messages: raw.total_messages + live.total_messages,
+        last_computed: if is_current {
+            cached_until.unwrap_or_else(|| today.clone())
+        } else {
+            today.clone()
+        },
+        first_session_date: raw
+            .first_session_date
+            .as_deref()
+            .and_then(normalize_timestamp)
+            .or(live.first_session_date),
+        daily_activity,
+        model_usage,
+        longest_session,
+        hour_counts,
         tokens_today,
         messages_today,
         sessions_today,
     })
 }
 
+/// Replace stats-cache model costs with estimates from a pricing table
+///
+/// Models without a known price keep the cost reported by Claude.
+pub fn apply_pricing(stats: &mut ClaudeStats, pricing: &PricingTable) {
+    for (model, usage) in stats.model_usage.iter_mut() {
+        if let Some(model_pricing) = pricing.for_model(model) {
+            usage.cost_usd = model_pricing.cost(&TokenUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cache_read_input_tokens: usage.cache_read_input_tokens,
+                cache_creation_input_tokens: usage.cache_creation_input_tokens,
+            });
+        }
+    }
+}
+
 /// Get total token count across all models
 pub fn get_total_tokens(stats: &ClaudeStats) -> u64 {
     stats
@@ -0,0 +1,362 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::sessions::truncate_string;
+use super::settings::{split_rule, PermissionBehavior, PermissionRule};
+
+/// Calls needed before a rule is worth suggesting
+const MIN_CALLS: u32 = 2;
+
+/// Example calls kept per suggestion
+const MAX_EXAMPLES: usize = 3;
+
+/// Commands that destroy work or escalate privileges: the program, its
+/// subcommand if any, and the flags that make it risky, or none if it always is
+const RISKY_COMMANDS: [(&str, Option<&str>, &[&str], &str); 9] = [
+    ("rm", None, &["-r", "-R", "--recursive"], "Recursively deletes files"),
+    ("sudo", None, &[], "Runs commands as root"),
+    ("git", Some("push"), &["-f", "--force", "--force-with-lease", "+"], "Rewrites remote history"),
+    ("git", Some("reset"), &["--hard"], "Discards uncommitted changes"),
+    ("git", Some("clean"), &[], "Deletes untracked files"),
+    ("chmod", None, &["-R", "--recursive"], "Recursively changes permissions"),
+    ("chown", None, &["-R", "--recursive"], "Recursively changes ownership"),
+    ("dd", None, &[], "Writes raw data to devices or files"),
+    ("mkfs", None, &[], "Formats a filesystem"),
+];
+
+/// A permission rule worth adding, mined from a project's tool calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionSuggestion {
+    pub rule: String,
+    pub behavior: PermissionBehavior, // Allow, or Ask for risky rules
+    pub calls: u32,
+    pub examples: Vec<String>,
+    pub risk: Option<String>, // Why the rule is risky to allow
+}
+
+#[derive(Default)]
+struct Candidate {
+    calls: u32,
+    examples: Vec<String>,
+    risk: Option<&'static str>,
+}
+
+fn is_env_assignment(token: &str) -> bool {
+    match token.split_once('=') {
+        Some((name, _)) => {
+            name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+/// Words of a command, without leading `VAR=value` assignments
+fn command_words(segment: &str) -> Vec<&str> {
+    segment
+        .split_whitespace()
+        .skip_while(|t| is_env_assignment(t))
+        .collect()
+}
+
+/// Split a shell command line into the commands it runs
+fn command_segments(command: &str) -> Vec<String> {
+    command
+        .replace(">&", ">")
+        .split(['&', '|', ';', '\n'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Rule prefix of a command: the program and its subcommand, e.g. `cargo test`
+fn bash_prefix(segment: &str) -> Option<String> {
+    let words = command_words(segment);
+    let program = words.first()?;
+
+    let plain_program = program.starts_with(|c: char| c.is_ascii_alphabetic() || c == '.' || c == '/')
+        && program.chars().all(|c| c.is_ascii_alphanumeric() || "._-/".contains(c));
+    if !plain_program {
+        return None;
+    }
+
+    match words.get(1) {
+        Some(sub)
+            if sub.starts_with(|c: char| c.is_ascii_lowercase())
+                && sub.chars().all(|c| c.is_ascii_lowercase() || c == '-' || c == ':') =>
+        {
+            Some(format!("{} {}", program, sub))
+        }
+        _ => Some(program.to_string()),
+    }
+}
+
+/// Whether any argument sets a flag, wherever it appears
+///
+/// Short flags also match when combined, e.g. `-rf` for `-r`, and `+` matches a
+/// forced refspec like `+main`.
+fn has_flag(args: &[&str], flag: &str) -> bool {
+    args.iter()
+        .take_while(|arg| **arg != "--")
+        .any(|arg| match flag.strip_prefix("--") {
+            Some(_) => *arg == flag || arg.starts_with(&format!("{}=", flag)),
+            None if flag == "+" => arg.starts_with('+'),
+            None => {
+                let short = flag.trim_start_matches('-');
+                arg.starts_with('-') && !arg.starts_with("--") && arg[1..].contains(short)
+            }
+        })
+}
+
+/// Why a command is risky to allow without asking, if it is
+fn command_risk(segment: &str) -> Option<&'static str> {
+    let words = command_words(segment);
+    let (program, args) = words.split_first()?;
+    let program = program.rsplit('/').next().unwrap_or(program);
+
+    RISKY_COMMANDS.iter().find_map(|(name, subcommand, flags, risk)| {
+        let matches = (program == *name || program.starts_with(&format!("{}.", name)))
+            // Anywhere, as options like `git -C dir` may come first
+            && subcommand.is_none_or(|sub| args.contains(&sub))
+            && (flags.is_empty() || flags.iter().any(|flag| has_flag(args, flag)));
+        matches.then_some(*risk)
+    })
+}
+
+/// Host of an http(s) URL
+fn web_domain(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let host = rest.split(['/', ':', '?', '#']).next()?.to_lowercase();
+
+    (!host.is_empty()).then_some(host)
+}
+
+/// Edit rule for a file inside the project: its top-level directory, or the file itself
+fn edit_rule(file_path: &str, project_path: &str) -> Option<String> {
+    let relative = Path::new(file_path).strip_prefix(project_path).ok()?;
+    let mut components = relative.components();
+    let first = components.next()?.as_os_str().to_str()?;
+
+    Some(if components.next().is_some() {
+        format!("Edit({}/**)", first)
+    } else {
+        format!("Edit({})", first)
+    })
+}
+
+/// Rules a tool call would need, with an example and a risk for each
+fn call_rules(
+    name: &str,
+    input: &Value,
+    project_path: &str,
+) -> Vec<(String, String, Option<&'static str>)> {
+    let field = |key: &str| input.get(key).and_then(|v| v.as_str());
+
+    match name {
+        "Bash" => field("command")
+            .map(command_segments)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|segment| {
+                let prefix = bash_prefix(&segment)?;
+                let risk = command_risk(&segment);
+                Some((format!("Bash({}:*)", prefix), segment, risk))
+            })
+            .collect(),
+        "WebFetch" => field("url")
+            .and_then(|url| Some((format!("WebFetch(domain:{})", web_domain(url)?), url.to_string(), None)))
+            .into_iter()
+            .collect(),
+        "Edit" | "MultiEdit" | "Write" => field("file_path")
+            .and_then(|path| Some((edit_rule(path, project_path)?, path.to_string(), None)))
+            .into_iter()
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Whether an existing rule already matches everything a candidate rule would
+fn covers(existing: &str, candidate: &str) -> bool {
+    let (Ok((tool, specifier)), Ok((candidate_tool, candidate_specifier))) =
+        (split_rule(existing), split_rule(candidate))
+    else {
+        return false;
+    };
+
+    if tool != candidate_tool {
+        return false;
+    }
+
+    let (specifier, candidate_specifier) = match (specifier, candidate_specifier) {
+        (None, _) => return true,
+        (Some(s), Some(c)) => (s, c),
+        (Some(_), None) => return false,
+    };
+
+    if specifier == candidate_specifier {
+        return true;
+    }
+    if let Some(prefix) = specifier.strip_suffix(":*") {
+        let candidate = candidate_specifier
+            .strip_suffix(":*")
+            .unwrap_or(candidate_specifier);
+        return candidate == prefix || candidate.starts_with(&format!("{} ", prefix));
+    }
+    if let Some(dir) = specifier.strip_suffix("**") {
+        return candidate_specifier.starts_with(dir);
+    }
+
+    false
+}
+
+/// Suggest rules for a project's frequent tool calls that no existing rule covers
+///
+/// Rules for risky commands are suggested as `ask` instead of `allow`.
+pub fn suggest_permission_rules(
+    conn: &Connection,
+    project_path: &str,
+    existing: &[PermissionRule],
+) -> Result<Vec<PermissionSuggestion>, String> {
+    // Tool call ids are unique, copies in resumed sessions are counted once
+    let mut stmt = conn
+        .prepare(
+            "SELECT tc.name, tc.input
+             FROM tool_calls tc
+             JOIN sessions s ON s.id = tc.session_id
+             WHERE s.project_path = ?1
+             GROUP BY tc.id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let calls = stmt
+        .query_map(params![project_path], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut candidates: HashMap<String, Candidate> = HashMap::new();
+
+    for (name, input) in calls {
+        let input: Value = serde_json::from_str(&input).unwrap_or(Value::Null);
+
+        for (rule, example, risk) in call_rules(&name, &input, project_path) {
+            let candidate = candidates.entry(rule).or_default();
+            candidate.calls += 1;
+            candidate.risk = candidate.risk.or(risk);
+
+            let example = truncate_string(&example, 80);
+            if candidate.examples.len() < MAX_EXAMPLES && !candidate.examples.contains(&example) {
+                candidate.examples.push(example);
+            }
+        }
+    }
+
+    let mut suggestions: Vec<PermissionSuggestion> = candidates
+        .into_iter()
+        .filter(|(rule, candidate)| {
+            candidate.calls >= MIN_CALLS && !existing.iter().any(|e| covers(&e.rule, rule))
+        })
+        .map(|(rule, candidate)| PermissionSuggestion {
+            rule,
+            behavior: if candidate.risk.is_some() {
+                PermissionBehavior::Ask
+            } else {
+                PermissionBehavior::Allow
+            },
+            calls: candidate.calls,
+            examples: candidate.examples,
+            risk: candidate.risk.map(String::from),
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.rule.cmp(&b.rule)));
+
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_rules() {
+        let segments = command_segments("RUST_LOG=debug cargo test 2>&1 | tail -5 && rm -rf target");
+        assert_eq!(segments, ["RUST_LOG=debug cargo test 2>1", "tail -5", "rm -rf target"]);
+
+        assert_eq!(bash_prefix(&segments[0]).as_deref(), Some("cargo test"));
+        assert_eq!(bash_prefix(&segments[1]).as_deref(), Some("tail"));
+        assert_eq!(bash_prefix("$(pwd)/run.sh"), None);
+
+        assert_eq!(command_risk(&segments[2]), Some("Recursively deletes files"));
+        assert_eq!(command_risk("rm target/old.log"), None);
+        assert_eq!(command_risk("ddate"), None);
+    }
+
+    #[test]
+    fn test_command_risk_flags_anywhere() {
+        assert_eq!(command_risk("git push origin main --force"), Some("Rewrites remote history"));
+        assert_eq!(command_risk("git push origin +main"), Some("Rewrites remote history"));
+        assert_eq!(command_risk("rm -r -f build"), Some("Recursively deletes files"));
+        assert_eq!(command_risk("/bin/rm -Rf build"), Some("Recursively deletes files"));
+        assert_eq!(command_risk("sudo -E rm build"), Some("Runs commands as root"));
+        assert_eq!(command_risk("mkfs.ext4 /dev/sdb1"), Some("Formats a filesystem"));
+
+        assert_eq!(command_risk("git push origin main"), None);
+        assert_eq!(command_risk("rm -f build.log"), None);
+        assert_eq!(command_risk("rm -- -r"), None);
+    }
+
+    #[test]
+    fn test_covers() {
+        assert!(covers("Bash(mix:*)", "Bash(mix test:*)"));
+        assert!(covers("Bash(mix test:*)", "Bash(mix test:*)"));
+        assert!(!covers("Bash(mi:*)", "Bash(mix test:*)"));
+        assert!(covers("WebFetch", "WebFetch(domain:hexdocs.pm)"));
+        assert!(covers("Edit(src/**)", "Edit(src/lib/**)"));
+        assert!(!covers("Edit(src/**)", "Bash(src:*)"));
+
+        assert_eq!(web_domain("https://HexDocs.pm/ecto?x=1").as_deref(), Some("hexdocs.pm"));
+        assert_eq!(edit_rule("/work/foo/lib/app.ex", "/work/foo").as_deref(), Some("Edit(lib/**)"));
+        assert_eq!(edit_rule("/tmp/notes.md", "/work/foo"), None);
+    }
+
+    #[test]
+    fn test_resumed_calls_counted_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO session_files (path, project_path) VALUES ('s.jsonl', '/work/app');
+             INSERT INTO sessions (id, project_path, project_name, first_message, started_at, last_activity)
+             VALUES ('s1', '/work/app', 'app', '', '', ''), ('s2', '/work/app', 'app', '', '', '');",
+        )
+        .unwrap();
+
+        // s2 resumes s1 and repeats t1
+        for (id, session_id, command) in [
+            ("t1", "s1", "cargo test"),
+            ("t1", "s2", "cargo test"),
+            ("t2", "s2", "cargo build"),
+            ("t3", "s2", "cargo build"),
+        ] {
+            conn.execute(
+                "INSERT INTO tool_calls (id, session_id, message_uuid, name, input, timestamp, file_path)
+                 VALUES (?1, ?2, 'u', 'Bash', ?3, '', 's.jsonl')",
+                params![id, session_id, serde_json::json!({ "command": command }).to_string()],
+            )
+            .unwrap();
+        }
+
+        let suggestions = suggest_permission_rules(&conn, "/work/app", &[]).unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].rule, "Bash(cargo build:*)");
+        assert_eq!(suggestions[0].calls, 2);
+    }
+}
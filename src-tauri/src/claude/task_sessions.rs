@@ -0,0 +1,322 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::costs::{session_cost, task_cost};
+use super::index::{get_project_sessions, get_session, FIRST_COPY};
+use super::pricing::{PricingTable, TokenUsage};
+use super::sessions::ClaudeSession;
+
+/// Share of a task's words a session's first message must contain to be suggested
+const MIN_SUGGESTION_SCORE: f64 = 0.5;
+
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Words too common to tell tasks apart
+const STOP_WORDS: [&str; 12] = [
+    "the", "and", "for", "with", "this", "that", "from", "into", "when", "then", "add", "make",
+];
+
+/// A session linked to a task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkedSession {
+    pub session_id: String,
+    pub source: String, // "manual", "run" by the task runner or "todo" when imported from a todo list
+    pub linked_at: String,
+    pub session: Option<ClaudeSession>, // None until the session is indexed
+    pub cost_usd: f64,
+}
+
+/// A session that looks like work on a task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSuggestion {
+    pub session: ClaudeSession,
+    pub score: f64, // Share of the task's words found in the first message
+}
+
+/// Claude usage of all sessions linked to a task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskUsage {
+    pub task_id: String,
+    pub session_count: u32,
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+    pub time_spent_secs: i64, // Gaps between messages shorter than the idle threshold
+}
+
+/// Link a session to a task, keeping the first link if it already exists
+pub fn attach(conn: &Connection, task_id: &str, session_id: &str, source: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO task_sessions (task_id, session_id, source, linked_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![task_id, session_id, source, chrono::Utc::now().to_rfc3339()],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+pub fn detach(conn: &Connection, task_id: &str, session_id: &str) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM task_sessions WHERE task_id = ?1 AND session_id = ?2",
+        params![task_id, session_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Sessions linked to a task, oldest link first
+pub fn linked_sessions(
+    conn: &Connection,
+    pricing: &PricingTable,
+    task_id: &str,
+) -> Result<Vec<LinkedSession>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT session_id, source, linked_at FROM task_sessions
+             WHERE task_id = ?1
+             ORDER BY linked_at",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let links = stmt
+        .query_map(params![task_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<(String, String, String)>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    links
+        .into_iter()
+        .map(|(session_id, source, linked_at)| {
+            Ok(LinkedSession {
+                session: get_session(conn, &session_id)?,
+                cost_usd: session_cost(conn, pricing, &session_id)?.cost_usd,
+                session_id,
+                source,
+                linked_at,
+            })
+        })
+        .collect()
+}
+
+/// Sessions, token usage, cost and time of the sessions linked to a task
+///
+/// Time counts the gaps between a session's messages, leaving out those of
+/// `idle_minutes` or longer when nobody was working. Messages a resumed
+/// session repeats count once, for tokens and cost as well as time.
+pub fn task_usage(
+    conn: &Connection,
+    pricing: &PricingTable,
+    task_id: &str,
+    idle_minutes: u32,
+) -> Result<TaskUsage, String> {
+    let idle = chrono::Duration::minutes(i64::from(idle_minutes.max(1)));
+    let cost = task_cost(conn, pricing, task_id)?;
+    let mut usage = TaskUsage {
+        task_id: task_id.to_string(),
+        session_count: 0,
+        total_tokens: cost.usage.input_tokens
+            + cost.usage.output_tokens
+            + cost.usage.cache_read_input_tokens
+            + cost.usage.cache_creation_input_tokens,
+        usage: cost.usage,
+        cost_usd: cost.cost_usd,
+        time_spent_secs: 0,
+    };
+
+    for linked in linked_sessions(conn, pricing, task_id)? {
+        usage.session_count += 1;
+        usage.time_spent_secs += active_secs(&message_timestamps(conn, &linked.session_id)?, idle);
+    }
+
+    Ok(usage)
+}
+
+/// Usage of every task in a project, or of all tasks
+pub fn project_task_usage(
+    conn: &Connection,
+    pricing: &PricingTable,
+    project_id: Option<&str>,
+    idle_minutes: u32,
+) -> Result<Vec<TaskUsage>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM tasks WHERE ?1 IS NULL OR project_id = ?1 ORDER BY created_at DESC")
+        .map_err(|e| e.to_string())?;
+
+    let task_ids = stmt
+        .query_map(params![project_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    task_ids
+        .iter()
+        .map(|id| task_usage(conn, pricing, id, idle_minutes))
+        .collect()
+}
+
+/// Timestamps of a session's own indexed messages, in order
+fn message_timestamps(conn: &Connection, session_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT m.timestamp FROM messages m WHERE m.session_id = ?1 AND {} ORDER BY m.timestamp",
+            FIRST_COPY
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let timestamps = stmt
+        .query_map(params![session_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(timestamps)
+}
+
+/// Sum of the gaps between consecutive messages that are shorter than `idle`
+fn active_secs(timestamps: &[String], idle: chrono::Duration) -> i64 {
+    let times: Vec<_> = timestamps
+        .iter()
+        .filter_map(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+        .collect();
+
+    let active: chrono::Duration = times
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .filter(|gap| *gap < idle)
+        .sum();
+
+    active.num_seconds()
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.chars().count() >= 3 && !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Share of a task's words that appear in a message
+fn match_score(task: &str, message: &str) -> f64 {
+    let task_words = words(task);
+    if task_words.is_empty() {
+        return 0.0;
+    }
+
+    let message_words = words(message);
+    let shared = task_words.intersection(&message_words).count();
+
+    shared as f64 / task_words.len() as f64
+}
+
+/// Unlinked sessions in the task's project whose first message matches the task
+pub fn suggest(conn: &Connection, task_id: &str) -> Result<Vec<SessionSuggestion>, String> {
+    let (content, project_path): (String, String) = conn
+        .query_row(
+            "SELECT t.content, p.path FROM tasks t JOIN projects p ON p.id = t.project_id WHERE t.id = ?1",
+            params![task_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT session_id FROM task_sessions WHERE task_id = ?1")
+        .map_err(|e| e.to_string())?;
+    let linked = stmt
+        .query_map(params![task_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<HashSet<String>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // Sessions come most recent first, and the sort keeps that order for equal scores
+    let mut suggestions: Vec<SessionSuggestion> = get_project_sessions(conn, &project_path)?
+        .into_iter()
+        .filter(|s| !linked.contains(&s.id))
+        .map(|session| SessionSuggestion {
+            score: match_score(&content, &session.first_message),
+            session,
+        })
+        .filter(|s| s.score >= MIN_SUGGESTION_SCORE)
+        .collect();
+
+    suggestions.sort_by(|a, b| b.score.total_cmp(&a.score));
+    suggestions.truncate(MAX_SUGGESTIONS);
+
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_score() {
+        let task = "Fix the flaky login test";
+
+        assert_eq!(match_score(task, "can you fix the login test? it's flaky on CI"), 1.0);
+        assert_eq!(match_score(task, "Fix the signup form"), 0.25);
+        assert_eq!(match_score("do it", "do it"), 0.0);
+    }
+
+    #[test]
+    fn test_active_secs() {
+        let timestamps: Vec<String> = [
+            "2026-03-01T10:00:00Z",
+            "2026-03-01T10:01:30.500Z",
+            "2026-03-01T10:04:00Z",
+            // Back after lunch, the hour away doesn't count
+            "2026-03-01T11:30:00Z",
+            "2026-03-01T11:31:00Z",
+        ]
+        .map(String::from)
+        .into();
+
+        assert_eq!(active_secs(&timestamps, chrono::Duration::minutes(5)), 300);
+        assert_eq!(active_secs(&timestamps, chrono::Duration::minutes(2)), 150);
+        assert_eq!(active_secs(&timestamps[..1], chrono::Duration::minutes(5)), 0);
+    }
+
+    #[test]
+    fn test_task_usage_counts_resumed_history_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init_schema(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, path) VALUES ('p1', 'app', '/work/app');
+             INSERT INTO tasks (id, project_id, content) VALUES ('t1', 'p1', 'Fix login');
+             INSERT INTO session_files (path, project_path) VALUES ('s.jsonl', '/work/app');",
+        )
+        .unwrap();
+
+        // s2 resumes s1, repeating u1 and u2
+        for (uuid, session_id, timestamp) in [
+            ("u1", "s1", "2026-03-01T10:00:00Z"),
+            ("u2", "s1", "2026-03-01T10:02:00Z"),
+            ("u1", "s2", "2026-03-01T10:00:00Z"),
+            ("u2", "s2", "2026-03-01T10:02:00Z"),
+            ("u3", "s2", "2026-03-01T10:03:00Z"),
+        ] {
+            conn.execute(
+                "INSERT INTO messages
+                 (uuid, session_id, msg_type, content, model, input_tokens, output_tokens, timestamp, file_path, line_offset)
+                 VALUES (?1, ?2, 'assistant', '', 'claude-sonnet-4-5', 100, 1000, ?3, 's.jsonl', 0)",
+                params![uuid, session_id, timestamp],
+            )
+            .unwrap();
+        }
+        attach(&conn, "t1", "s1", "manual").unwrap();
+        attach(&conn, "t1", "s2", "manual").unwrap();
+
+        let usage = task_usage(&conn, &PricingTable::builtin(), "t1", 5).unwrap();
+        assert_eq!(usage.session_count, 2);
+        assert_eq!(usage.usage.output_tokens, 3000);
+        assert_eq!(usage.total_tokens, 3300);
+        assert!((usage.cost_usd - (300.0 * 3.0 + 3000.0 * 15.0) / 1_000_000.0).abs() < 1e-9);
+        // s1's two minutes, then the minute s2 added after resuming
+        assert_eq!(usage.time_spent_secs, 120);
+    }
+}
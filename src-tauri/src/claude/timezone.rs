@@ -0,0 +1,104 @@
+use chrono::{DateTime, FixedOffset, Local, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::Connection;
+
+/// Time zone used to bucket activity into calendar days
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayZone {
+    /// The system's local time zone
+    Local,
+    /// An IANA time zone such as `Asia/Kolkata`
+    Named(Tz),
+}
+
+impl DayZone {
+    /// Parse an IANA time zone name, using the system zone when unset
+    pub fn from_name(name: Option<&str>) -> Result<Self, String> {
+        match name.map(str::trim).filter(|n| !n.is_empty()) {
+            None => Ok(DayZone::Local),
+            Some(name) => name
+                .parse::<Tz>()
+                .map(DayZone::Named)
+                .map_err(|_| format!("Unknown timezone: {}", name)),
+        }
+    }
+
+    fn format<T: TimeZone>(&self, time: &DateTime<T>, fmt: &str) -> String {
+        match self {
+            DayZone::Local => time.with_timezone(&Local).format(fmt).to_string(),
+            DayZone::Named(tz) => time.with_timezone(tz).format(fmt).to_string(),
+        }
+    }
+
+    /// Local date of a point in time, `YYYY-MM-DD`
+    pub fn date<T: TimeZone>(&self, time: &DateTime<T>) -> String {
+        self.format(time, "%Y-%m-%d")
+    }
+
+    /// Local hour of a point in time, `0` to `23`
+    pub fn hour<T: TimeZone>(&self, time: &DateTime<T>) -> String {
+        self.format(time, "%-H")
+    }
+
+    /// Today's local date, `YYYY-MM-DD`
+    pub fn today(&self) -> String {
+        self.date(&Utc::now())
+    }
+
+    /// Fraction of today's local day that has passed, `0.0` to `1.0`
+    pub fn day_elapsed(&self) -> f64 {
+        let now = Utc::now();
+        let seconds = match self {
+            DayZone::Local => now.with_timezone(&Local).num_seconds_from_midnight(),
+            DayZone::Named(tz) => now.with_timezone(tz).num_seconds_from_midnight(),
+        };
+
+        seconds as f64 / 86_400.0
+    }
+
+    /// Local date of an RFC 3339 timestamp
+    pub fn date_of(&self, timestamp: &str) -> Option<String> {
+        DateTime::<FixedOffset>::parse_from_rfc3339(timestamp)
+            .ok()
+            .map(|time| self.date(&time))
+    }
+
+    /// Register `local_date(timestamp)` on a connection so queries can group by local day
+    pub fn register_sql_functions(self, conn: &Connection) -> Result<(), String> {
+        conn.create_scalar_function(
+            "local_date",
+            1,
+            FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+            move |ctx| {
+                let timestamp: Option<String> = ctx.get(0)?;
+                Ok(timestamp.and_then(|ts| self.date_of(&ts)))
+            },
+        )
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_zone_buckets_by_local_date() {
+        let zone = DayZone::from_name(Some("Asia/Kolkata")).unwrap();
+
+        // 20:00 UTC is 01:30 the next day in UTC+5:30
+        assert_eq!(zone.date_of("2025-01-01T20:00:00.000Z").as_deref(), Some("2025-01-02"));
+        assert_eq!(zone.date_of("2025-01-01T18:29:59Z").as_deref(), Some("2025-01-01"));
+
+        let time = DateTime::parse_from_rfc3339("2025-01-01T20:00:00Z").unwrap();
+        assert_eq!(zone.hour(&time), "1");
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(DayZone::from_name(None).unwrap(), DayZone::Local);
+        assert_eq!(DayZone::from_name(Some(" ")).unwrap(), DayZone::Local);
+        assert!(DayZone::from_name(Some("Mars/Olympus_Mons")).is_err());
+    }
+}
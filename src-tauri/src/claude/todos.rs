@@ -0,0 +1,206 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use super::index::get_project_sessions;
+use super::paths::get_todos_dir;
+
+/// An item of a todo list Claude keeps with the TodoWrite tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TodoItem {
+    pub content: String,
+    pub status: String, // "pending", "in_progress" or "completed"
+    pub active_form: Option<String>, // Shown while in progress, e.g. "Running tests"
+    #[serde(default)]
+    pub imported: bool, // A task with the same content exists in the project
+}
+
+/// The latest todo list of a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTodos {
+    pub session_id: String,
+    pub first_message: String,
+    pub source: String, // "tool_call" or "todo_file", whichever is newer
+    pub updated_at: String,
+    pub items: Vec<TodoItem>,
+}
+
+/// Items of a TodoWrite input or a todo file
+pub fn parse_todos(todos: &Value) -> Vec<TodoItem> {
+    todos
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|todo| {
+            let content = todo["content"].as_str()?.trim();
+            if content.is_empty() {
+                return None;
+            }
+
+            Some(TodoItem {
+                content: content.to_string(),
+                status: todo["status"].as_str().unwrap_or("pending").to_string(),
+                active_form: todo["activeForm"].as_str().map(String::from),
+                imported: false,
+            })
+        })
+        .collect()
+}
+
+/// Task content compared case and whitespace insensitively
+pub fn normalize_content(content: &str) -> String {
+    content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Content of a project's tasks, normalized
+pub fn task_contents(conn: &Connection, project_id: &str) -> Result<HashSet<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT content FROM tasks WHERE project_id = ?1")
+        .map_err(|e| e.to_string())?;
+
+    let contents = stmt
+        .query_map(params![project_id], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .map(|content| content.map(|c| normalize_content(&c)))
+        .collect::<Result<HashSet<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(contents)
+}
+
+/// Items not yet in `existing`, first of each content only
+pub fn new_items<'a>(existing: &HashSet<String>, items: &'a [TodoItem]) -> Vec<&'a TodoItem> {
+    let mut seen = existing.clone();
+
+    items
+        .iter()
+        .filter(|item| seen.insert(normalize_content(&item.content)))
+        .collect()
+}
+
+/// Latest TodoWrite input of each session in a project, with its timestamp
+fn latest_tool_calls(conn: &Connection, project_path: &str) -> Result<HashMap<String, (String, Value)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT tc.session_id, tc.timestamp, tc.input
+             FROM tool_calls tc
+             JOIN sessions s ON s.id = tc.session_id
+             WHERE s.project_path = ?1 AND tc.name = 'TodoWrite'
+             ORDER BY tc.timestamp",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![project_path], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut latest = HashMap::new();
+    for row in rows {
+        let (session_id, timestamp, input) = row.map_err(|e| e.to_string())?;
+        let input: Value = serde_json::from_str(&input).unwrap_or_default();
+        latest.insert(session_id, (timestamp, input["todos"].clone()));
+    }
+
+    Ok(latest)
+}
+
+/// The main agent's todo file of a session and when it was written
+fn read_todo_file(todos_dir: &Path, session_id: &str) -> Option<(String, Value)> {
+    let path = todos_dir.join(format!("{}-agent-{}.json", session_id, session_id));
+    let modified = fs::metadata(&path).ok()?.modified().ok()?;
+    let todos = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+
+    Some((chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339(), todos))
+}
+
+/// The latest non-empty todo list of each session in a project, most recent first
+///
+/// Lists come from indexed TodoWrite calls and from ~/.claude/todos, whichever
+/// was written last.
+pub fn session_todos(conn: &Connection, project_id: &str, project_path: &str) -> Result<Vec<SessionTodos>, String> {
+    let mut tool_calls = latest_tool_calls(conn, project_path)?;
+    let todos_dir = get_todos_dir();
+    let existing = task_contents(conn, project_id)?;
+    let mut lists = vec![];
+
+    for session in get_project_sessions(conn, project_path)? {
+        let from_call = tool_calls.remove(&session.id).map(|latest| ("tool_call", latest));
+        let from_file = todos_dir
+            .as_deref()
+            .and_then(|dir| read_todo_file(dir, &session.id))
+            .map(|latest| ("todo_file", latest));
+
+        let newest = [from_call, from_file]
+            .into_iter()
+            .flatten()
+            .filter(|(_, (_, todos))| !parse_todos(todos).is_empty())
+            .max_by_key(|(_, (at, _))| chrono::DateTime::parse_from_rfc3339(at).ok());
+
+        if let Some((source, (updated_at, todos))) = newest {
+            let items = parse_todos(&todos)
+                .into_iter()
+                .map(|item| TodoItem {
+                    imported: existing.contains(&normalize_content(&item.content)),
+                    ..item
+                })
+                .collect();
+
+            lists.push(SessionTodos {
+                session_id: session.id,
+                first_message: session.first_message,
+                source: source.to_string(),
+                updated_at,
+                items,
+            });
+        }
+    }
+
+    lists.sort_by_key(|list| std::cmp::Reverse(chrono::DateTime::parse_from_rfc3339(&list.updated_at).ok()));
+
+    Ok(lists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_todos() {
+        let input: Value = serde_json::json!([
+            { "content": "Run the test suite", "status": "in_progress", "activeForm": "Running the test suite" },
+            { "content": "  ", "status": "pending" },
+            { "content": "Fix type errors" }
+        ]);
+
+        let items = parse_todos(&input);
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].active_form.as_deref(), Some("Running the test suite"));
+        assert_eq!(items[1].status, "pending");
+        assert!(parse_todos(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_new_items() {
+        let existing = HashSet::from([normalize_content("Fix  type errors")]);
+        let items = parse_todos(&serde_json::json!([
+            { "content": "fix type errors", "status": "completed" },
+            { "content": "Update the changelog", "status": "pending" },
+            { "content": "update the  changelog", "status": "pending" }
+        ]));
+
+        let new = new_items(&existing, &items);
+
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].content, "Update the changelog");
+    }
+}
@@ -0,0 +1,32 @@
+use crate::approvals::{self, ApprovalDecision, Approvals, PendingApproval};
+use crate::db::Database;
+use tauri::State;
+
+/// Get tool calls waiting for approval, oldest first
+#[tauri::command]
+pub fn get_pending_approvals(approvals: State<Approvals>) -> Result<Vec<PendingApproval>, String> {
+    approvals.pending()
+}
+
+/// Allow, deny or defer to Claude's own prompt ("ask") for a pending tool call
+#[tauri::command]
+pub fn resolve_approval(
+    approvals: State<Approvals>,
+    id: String,
+    decision: String,
+    reason: Option<String>,
+) -> Result<(), String> {
+    approvals.resolve(&id, &decision, reason)
+}
+
+/// Get audited approval decisions, newest first, optionally for a single session
+#[tauri::command]
+pub fn get_approval_audit(
+    db: State<Database>,
+    session_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<ApprovalDecision>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    approvals::list_decisions(&conn, session_id.as_deref(), limit.unwrap_or(100))
+}
@@ -0,0 +1,16 @@
+use crate::budget::{self, BudgetStatus};
+use crate::claude::index;
+use crate::commands::config::{load_config, load_day_zone};
+use crate::db::Database;
+use tauri::State;
+
+/// Get today's token usage against the daily limit, with projected end-of-day usage
+#[tauri::command]
+pub fn get_budget_status(db: State<Database>) -> Result<BudgetStatus, String> {
+    let config = load_config()?;
+    let zone = load_day_zone()?;
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    index::refresh_tracked_projects(&mut conn)?;
+    budget::get_status(&conn, &config, zone)
+}
@@ -1,49 +1,122 @@
 use crate::claude::{ClaudeMessage, ClaudeSession, ClaudeStats};
+use crate::claude::errors::{self, SessionError};
+use crate::claude::index;
+use crate::claude::pricing::PricingTable;
+use crate::claude::search::{self, SessionSearchResult};
 use crate::claude::sessions;
 use crate::claude::stats;
-use crate::db::Database;
+use crate::commands::config::load_day_zone;
+use crate::commands::projects::project_path;
+use crate::db::{get_pricing_path, Database};
 use tauri::State;
 
-/// Get usage statistics from Claude's stats-cache.json
+/// Get usage statistics from Claude's stats-cache.json and the session index
+///
+/// Like the cache, days it doesn't cover yet count every project, tracked or
+/// not, so all of them are refreshed first.
 #[tauri::command]
-pub fn get_claude_stats() -> Result<ClaudeStats, String> {
-    stats::get_claude_stats()
+pub fn get_claude_stats(db: State<Database>) -> Result<ClaudeStats, String> {
+    let mut claude_stats = {
+        let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+        index::refresh_all_projects(&mut conn)?;
+        stats::get_claude_stats(&conn, load_day_zone()?)?
+    };
+
+    let pricing = PricingTable::load(&get_pricing_path()?)?;
+    stats::apply_pricing(&mut claude_stats, &pricing);
+
+    Ok(claude_stats)
 }
 
-/// Get sessions for a specific project
+/// Get sessions for a specific project, refreshing its index first
 #[tauri::command]
-pub fn get_project_sessions(project_path: String) -> Result<Vec<ClaudeSession>, String> {
-    sessions::get_project_sessions(&project_path)
+pub fn get_project_sessions(db: State<Database>, project_path: String) -> Result<Vec<ClaudeSession>, String> {
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    index::refresh_project(&mut conn, &project_path)?;
+    index::get_project_sessions(&conn, &project_path)
 }
 
 /// Get recent sessions for tracked projects only
 #[tauri::command]
 pub fn get_recent_sessions(db: State<Database>, limit: u32) -> Result<Vec<ClaudeSession>, String> {
-    // Get tracked project paths from database
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
-    let mut stmt = conn
-        .prepare("SELECT path FROM projects")
-        .map_err(|e| e.to_string())?;
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    index::refresh_tracked_projects(&mut conn)?;
+    index::get_recent_sessions(&conn, limit)
+}
+
+/// Search session messages of tracked projects
+#[tauri::command]
+pub fn search_sessions(
+    db: State<Database>,
+    query: String,
+    project_id: Option<String>,
+    date_from: Option<String>,
+    date_to: Option<String>,
+) -> Result<Vec<SessionSearchResult>, String> {
+    let zone = load_day_zone()?;
+    let project_path = project_id.map(|id| project_path(&db, &id)).transpose()?;
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    index::refresh_tracked_projects(&mut conn)?;
+    search::search_sessions(
+        &conn,
+        &query,
+        project_path.as_deref(),
+        date_from.as_deref(),
+        date_to.as_deref(),
+        zone,
+    )
+}
 
-    let project_paths: Vec<String> = stmt
-        .query_map([], |row| row.get(0))
-        .map_err(|e| e.to_string())?
-        .filter_map(|r| r.ok())
-        .collect();
+/// Get messages for a specific session, optionally paginated
+///
+/// Indexed sessions are paged from the index and only the lines of the page
+/// are read. Sessions not indexed yet, or whose files changed since, are
+/// parsed in full.
+#[tauri::command]
+pub fn get_session_messages(
+    db: State<Database>,
+    session_id: String,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> Result<Vec<ClaudeMessage>, String> {
+    let (indexed, page, known_files) = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        (
+            index::is_session_indexed(&conn, &session_id)?,
+            index::get_message_page(&conn, &session_id, offset.unwrap_or(0), limit)?,
+            index::find_session_files(&conn, &session_id)?,
+        )
+    };
 
-    drop(stmt);
-    drop(conn);
+    if indexed {
+        let lines: Vec<_> = page.iter().map(|(path, line_offset, _)| (path.clone(), *line_offset)).collect();
+        let read = sessions::read_messages_at(&lines).unwrap_or_default();
+        let current = read.len() == page.len()
+            && read.iter().zip(&page).all(|(msg, (_, _, uuid))| {
+                msg.as_ref().is_some_and(|m| &m.uuid == uuid && m.session_id == session_id)
+            });
 
-    if project_paths.is_empty() {
-        return Ok(vec![]);
+        if current {
+            return Ok(read.into_iter().flatten().collect());
+        }
     }
 
-    // Get sessions only for tracked projects
-    sessions::get_sessions_for_projects(&project_paths, limit)
+    let messages = sessions::get_session_messages(&session_id, &known_files)?;
+
+    Ok(messages
+        .into_iter()
+        .skip(offset.unwrap_or(0) as usize)
+        .take(limit.map(|l| l as usize).unwrap_or(usize::MAX))
+        .collect())
 }
 
-/// Get messages for a specific session
+/// Get the errors detected in a session, each pointing at its message
 #[tauri::command]
-pub fn get_session_messages(session_id: String) -> Result<Vec<ClaudeMessage>, String> {
-    sessions::get_session_messages(&session_id)
+pub fn get_session_errors(db: State<Database>, session_id: String) -> Result<Vec<SessionError>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    errors::get_session_errors(&conn, &session_id)
 }
@@ -1,9 +1,12 @@
+use crate::approvals::{APPROVAL_DECISIONS, MAX_APPROVAL_TIMEOUT_SECS};
+use crate::claude::timezone::DayZone;
 use crate::db::get_config_path;
+use crate::launcher::PERMISSION_MODES;
 use crate::models::Config;
 use std::fs;
 
-#[tauri::command]
-pub fn get_config() -> Result<Config, String> {
+/// Read the config file, falling back to defaults when it doesn't exist
+pub fn load_config() -> Result<Config, String> {
     let config_path = get_config_path()?;
 
     if !config_path.exists() {
@@ -20,8 +23,50 @@ pub fn get_config() -> Result<Config, String> {
     Ok(config)
 }
 
+/// Time zone configured for bucketing activity into days
+pub fn load_day_zone() -> Result<DayZone, String> {
+    DayZone::from_name(load_config()?.timezone.as_deref())
+}
+
+#[tauri::command]
+pub fn get_config() -> Result<Config, String> {
+    load_config()
+}
+
 #[tauri::command]
 pub fn save_config(config: Config) -> Result<(), String> {
+    DayZone::from_name(config.timezone.as_deref())?;
+
+    if config.budget_thresholds.contains(&0) {
+        return Err("Budget thresholds must be above 0%".to_string());
+    }
+
+    if !APPROVAL_DECISIONS.contains(&config.approval_default.as_str()) {
+        return Err("Invalid approval default. Must be 'allow', 'deny', or 'ask'".to_string());
+    }
+
+    if config.approval_timeout_secs == 0 || config.approval_timeout_secs > MAX_APPROVAL_TIMEOUT_SECS {
+        return Err(format!(
+            "Approval timeout must be between 1 and {} seconds",
+            MAX_APPROVAL_TIMEOUT_SECS
+        ));
+    }
+
+    if config.memory_file_token_limit == 0 || config.memory_total_token_limit == 0 {
+        return Err("Memory token limits must be above 0".to_string());
+    }
+
+    if config.task_concurrency == 0 {
+        return Err("Task concurrency must be at least 1".to_string());
+    }
+
+    if !PERMISSION_MODES.contains(&config.task_permission_mode.as_str()) {
+        return Err(format!(
+            "Invalid task permission mode. Must be one of {}",
+            PERMISSION_MODES.join(", ")
+        ));
+    }
+
     let config_path = get_config_path()?;
 
     let content = serde_json::to_string_pretty(&config)
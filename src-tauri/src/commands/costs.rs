@@ -0,0 +1,60 @@
+use crate::claude::costs::{self, CostSummary, MessageCost};
+use crate::claude::index;
+use crate::claude::pricing::PricingTable;
+use crate::commands::config::load_day_zone;
+use crate::commands::projects::project_path;
+use crate::db::{get_pricing_path, Database};
+use tauri::State;
+
+pub fn load_pricing() -> Result<PricingTable, String> {
+    PricingTable::load(&get_pricing_path()?)
+}
+
+/// Get the effective pricing table (built-in prices with user overrides)
+#[tauri::command]
+pub fn get_pricing() -> Result<PricingTable, String> {
+    load_pricing()
+}
+
+/// Get the cost of each message in a session
+#[tauri::command]
+pub fn get_message_costs(db: State<Database>, session_id: String) -> Result<Vec<MessageCost>, String> {
+    let pricing = load_pricing()?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    costs::message_costs(&conn, &pricing, &session_id)
+}
+
+/// Get the total cost of a session
+#[tauri::command]
+pub fn get_session_cost(db: State<Database>, session_id: String) -> Result<CostSummary, String> {
+    let pricing = load_pricing()?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    costs::session_cost(&conn, &pricing, &session_id)
+}
+
+/// Get the total cost of each tracked project, keyed by project path
+#[tauri::command]
+pub fn get_project_costs(db: State<Database>) -> Result<Vec<CostSummary>, String> {
+    let pricing = load_pricing()?;
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    index::refresh_tracked_projects(&mut conn)?;
+    costs::project_costs(&conn, &pricing)
+}
+
+/// Get the cost per local day, optionally for a single project
+#[tauri::command]
+pub fn get_daily_costs(
+    db: State<Database>,
+    project_id: Option<String>,
+) -> Result<Vec<CostSummary>, String> {
+    let pricing = load_pricing()?;
+    let zone = load_day_zone()?;
+    let project_path = project_id.map(|id| project_path(&db, &id)).transpose()?;
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    index::refresh_tracked_projects(&mut conn)?;
+    costs::daily_costs(&conn, &pricing, project_path.as_deref(), zone)
+}
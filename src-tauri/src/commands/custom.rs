@@ -0,0 +1,76 @@
+use crate::claude::custom::{self, CustomDefinition, CustomFile, CustomKind, CustomScope};
+use crate::claude::index;
+use crate::commands::projects::project_path;
+use crate::db::Database;
+use tauri::State;
+
+fn list(db: &State<Database>, project_path: &str) -> Result<Vec<CustomFile>, String> {
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    index::refresh_project_if_present(&mut conn, project_path)?;
+    custom::list(&conn, project_path)
+}
+
+/// Get a project's slash commands and subagents with how often each was used
+#[tauri::command]
+pub fn get_custom_files(db: State<Database>, project_id: String) -> Result<Vec<CustomFile>, String> {
+    list(&db, &project_path(&db, &project_id)?)
+}
+
+/// Create a slash command or subagent, returning the updated list
+#[tauri::command]
+pub fn create_custom_file(
+    db: State<Database>,
+    project_id: String,
+    kind: CustomKind,
+    scope: CustomScope,
+    name: String,
+    namespace: Option<String>,
+    definition: CustomDefinition,
+) -> Result<Vec<CustomFile>, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    custom::create(&project_path, kind, scope, &name, namespace.as_deref(), &definition)?;
+    list(&db, &project_path)
+}
+
+/// Replace a slash command's or subagent's fields, returning the updated list
+#[tauri::command]
+pub fn update_custom_file(
+    db: State<Database>,
+    project_id: String,
+    path: String,
+    definition: CustomDefinition,
+) -> Result<Vec<CustomFile>, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    custom::update(&project_path, &path, &definition)?;
+    list(&db, &project_path)
+}
+
+/// Rename a slash command or subagent, returning the updated list
+#[tauri::command]
+pub fn rename_custom_file(
+    db: State<Database>,
+    project_id: String,
+    path: String,
+    new_name: String,
+) -> Result<Vec<CustomFile>, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    custom::rename(&project_path, &path, &new_name)?;
+    list(&db, &project_path)
+}
+
+/// Delete a slash command or subagent, returning the updated list
+#[tauri::command]
+pub fn delete_custom_file(
+    db: State<Database>,
+    project_id: String,
+    path: String,
+) -> Result<Vec<CustomFile>, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    custom::delete(&project_path, &path)?;
+    list(&db, &project_path)
+}
@@ -0,0 +1,39 @@
+use crate::claude::settings::SettingsScope;
+use crate::db::Database;
+use crate::hooks::{self, HookEvent};
+use rusqlite::params;
+use tauri::State;
+
+/// Get recent hook events, newest first, optionally for a single session
+#[tauri::command]
+pub fn get_hook_events(
+    db: State<Database>,
+    session_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<HookEvent>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    hooks::list_events(&conn, session_id.as_deref(), limit.unwrap_or(100))
+}
+
+/// Install Tusker's hooks into a project's settings, `.claude/settings.local.json`
+/// unless another scope is given, returning the settings file's path
+#[tauri::command]
+pub fn install_hooks(
+    db: State<Database>,
+    project_id: String,
+    scope: Option<SettingsScope>,
+) -> Result<String, String> {
+    let project_path: String = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT path FROM projects WHERE id = ?1",
+            params![&project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?
+    };
+
+    let settings_path = hooks::install_hooks(&project_path, scope.unwrap_or(SettingsScope::Local))?;
+    Ok(settings_path.to_string_lossy().to_string())
+}
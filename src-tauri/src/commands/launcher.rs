@@ -0,0 +1,31 @@
+use crate::commands::config::load_config;
+use crate::commands::projects::project_path;
+use crate::db::Database;
+use crate::launcher::{self, ClaudeProcess, LaunchOptions, Launcher};
+use tauri::{AppHandle, State};
+
+/// Start a Claude session in a project's directory
+#[tauri::command]
+pub fn launch_claude(
+    app: AppHandle,
+    db: State<Database>,
+    launcher: State<Launcher>,
+    project_id: String,
+    options: LaunchOptions,
+) -> Result<ClaudeProcess, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    launcher::launch(&app, &launcher, &load_config()?, &project_id, &project_path, &options)
+}
+
+/// Stop a Claude process started from Tusker
+#[tauri::command]
+pub fn stop_claude(launcher: State<Launcher>, process_id: String) -> Result<(), String> {
+    launcher.stop(&process_id)
+}
+
+/// Get Claude processes started since the app launched, newest first
+#[tauri::command]
+pub fn get_claude_processes(launcher: State<Launcher>) -> Result<Vec<ClaudeProcess>, String> {
+    launcher.list()
+}
@@ -0,0 +1,69 @@
+use crate::claude::mcp::{self, McpScope, McpServer, McpServerConfig};
+use crate::commands::projects::project_path;
+use crate::db::Database;
+use tauri::State;
+
+/// Get the MCP servers configured for a project, with secrets masked
+#[tauri::command]
+pub fn get_mcp_servers(db: State<Database>, project_id: String) -> Result<Vec<McpServer>, String> {
+    mcp::list_servers(&project_path(&db, &project_id)?)
+}
+
+/// Add an MCP server to a scope, returning the updated list
+#[tauri::command]
+pub fn add_mcp_server(
+    db: State<Database>,
+    project_id: String,
+    scope: McpScope,
+    name: String,
+    config: McpServerConfig,
+) -> Result<Vec<McpServer>, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    mcp::add_server(&project_path, scope, &name, &config)?;
+    mcp::list_servers(&project_path)
+}
+
+/// Replace an MCP server's config, returning the updated list
+#[tauri::command]
+pub fn update_mcp_server(
+    db: State<Database>,
+    project_id: String,
+    scope: McpScope,
+    name: String,
+    config: McpServerConfig,
+) -> Result<Vec<McpServer>, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    mcp::update_server(&project_path, scope, &name, &config)?;
+    mcp::list_servers(&project_path)
+}
+
+/// Remove an MCP server from a scope, returning the updated list
+#[tauri::command]
+pub fn remove_mcp_server(
+    db: State<Database>,
+    project_id: String,
+    scope: McpScope,
+    name: String,
+) -> Result<Vec<McpServer>, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    mcp::remove_server(&project_path, scope, &name)?;
+    mcp::list_servers(&project_path)
+}
+
+/// Switch an MCP server on or off for a project, returning the updated list
+#[tauri::command]
+pub fn set_mcp_server_enabled(
+    db: State<Database>,
+    project_id: String,
+    scope: McpScope,
+    name: String,
+    enabled: bool,
+) -> Result<Vec<McpServer>, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    mcp::set_server_enabled(&project_path, scope, &name, enabled)?;
+    mcp::list_servers(&project_path)
+}
@@ -0,0 +1,82 @@
+use crate::claude::lint::{self, MemoryLintReport};
+use crate::claude::memory::{self, DiffLine, MemoryFile, MemoryVersion};
+use crate::commands::config::load_config;
+use crate::commands::projects::project_path;
+use crate::db::Database;
+use tauri::State;
+
+/// Get the CLAUDE.md and memory files Claude loads for a project
+#[tauri::command]
+pub fn get_memory_files(db: State<Database>, project_id: String) -> Result<Vec<MemoryFile>, String> {
+    Ok(memory::discover(&project_path(&db, &project_id)?))
+}
+
+/// Read one of a project's memory files
+#[tauri::command]
+pub fn read_memory_file(db: State<Database>, project_id: String, path: String) -> Result<String, String> {
+    let file = memory::find(&project_path(&db, &project_id)?, &path)?;
+    memory::read(&file)
+}
+
+/// Save one of a project's memory files, recording a revision
+#[tauri::command]
+pub fn save_memory_file(
+    db: State<Database>,
+    project_id: String,
+    path: String,
+    content: String,
+) -> Result<MemoryVersion, String> {
+    let file = memory::find(&project_path(&db, &project_id)?, &path)?;
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    memory::save(&conn, &file, &content, "save")
+}
+
+/// Get the saved revisions of a memory file, newest first
+#[tauri::command]
+pub fn get_memory_versions(db: State<Database>, path: String) -> Result<Vec<MemoryVersion>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    memory::list_versions(&conn, &path)
+}
+
+/// Get the content of a saved revision
+#[tauri::command]
+pub fn get_memory_version_content(db: State<Database>, id: i64) -> Result<String, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    memory::version_content(&conn, id).map(|(_, content)| content)
+}
+
+/// Get the changes a revision made to the one before it
+#[tauri::command]
+pub fn diff_memory_version(db: State<Database>, id: i64) -> Result<Vec<DiffLine>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    memory::diff_version(&conn, id)
+}
+
+/// Write a saved revision back to its file, recording the restore as a new revision
+#[tauri::command]
+pub fn restore_memory_version(
+    db: State<Database>,
+    project_id: String,
+    id: i64,
+) -> Result<MemoryVersion, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let (path, content) = memory::version_content(&conn, id)?;
+    let file = memory::find(&project_path, &path)?;
+
+    memory::save(&conn, &file, &content, "restore")
+}
+
+/// Report token sizes, broken imports and duplicated sections in a project's memory files
+#[tauri::command]
+pub fn lint_project_memory(db: State<Database>, project_id: String) -> Result<MemoryLintReport, String> {
+    let config = load_config()?;
+
+    Ok(lint::lint_project_memory(
+        &project_path(&db, &project_id)?,
+        config.memory_file_token_limit,
+        config.memory_total_token_limit,
+    ))
+}
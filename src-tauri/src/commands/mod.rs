@@ -2,3 +2,16 @@ pub mod projects;
 pub mod tasks;
 pub mod config;
 pub mod claude;
+pub mod costs;
+pub mod budget;
+pub mod notifications;
+pub mod hooks;
+pub mod approvals;
+pub mod settings;
+pub mod memory;
+pub mod custom;
+pub mod mcp;
+pub mod launcher;
+pub mod runner;
+pub mod task_sessions;
+pub mod todos;
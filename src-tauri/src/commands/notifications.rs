@@ -0,0 +1,56 @@
+use crate::db::Database;
+use crate::models::Notification;
+use crate::notifications;
+use rusqlite::params;
+use tauri::State;
+
+/// Get notifications newest first, optionally of a single type
+#[tauri::command]
+pub fn get_notifications(
+    db: State<Database>,
+    notification_type: Option<String>,
+) -> Result<Vec<Notification>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    notifications::list(&conn, notification_type.as_deref())
+}
+
+#[tauri::command]
+pub fn mark_notification_read(db: State<Database>, id: String) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    conn.execute("UPDATE notifications SET read = 1 WHERE id = ?1", params![&id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn mark_all_notifications_read(db: State<Database>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    conn.execute("UPDATE notifications SET read = 1 WHERE read = 0", [])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_notification(db: State<Database>, id: String) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    conn.execute("DELETE FROM notifications WHERE id = ?1", params![&id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_notifications(db: State<Database>) -> Result<(), String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    conn.execute("DELETE FROM notifications", [])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
@@ -5,6 +5,17 @@ use std::path::Path;
 use tauri::State;
 use uuid::Uuid;
 
+/// Path of a tracked project
+pub fn project_path(db: &State<Database>, project_id: &str) -> Result<String, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    conn.query_row(
+        "SELECT path FROM projects WHERE id = ?1",
+        params![project_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_projects(db: State<Database>) -> Result<Vec<Project>, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
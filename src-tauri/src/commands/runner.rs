@@ -0,0 +1,42 @@
+use crate::db::Database;
+use crate::runner::{self, TaskRun};
+use tauri::{AppHandle, State};
+
+/// Queue a pending task to run headless with `claude -p`
+#[tauri::command]
+pub fn run_task(app: AppHandle, task_id: String) -> Result<Vec<TaskRun>, String> {
+    runner::enqueue(&app, &[task_id])
+}
+
+/// Queue all of a project's pending tasks, oldest first
+#[tauri::command]
+pub fn run_project_tasks(
+    app: AppHandle,
+    db: State<Database>,
+    project_id: String,
+) -> Result<Vec<TaskRun>, String> {
+    let task_ids = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        runner::pending_task_ids(&conn, &project_id)?
+    };
+
+    runner::enqueue(&app, &task_ids)
+}
+
+/// Cancel a queued task run or stop a running one
+#[tauri::command]
+pub fn cancel_task_run(app: AppHandle, run_id: String) -> Result<(), String> {
+    runner::cancel(&app, &run_id)
+}
+
+/// Get task runs, newest first, optionally for a single project or task
+#[tauri::command]
+pub fn get_task_runs(
+    db: State<Database>,
+    project_id: Option<String>,
+    task_id: Option<String>,
+) -> Result<Vec<TaskRun>, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    runner::list_runs(&conn, project_id.as_deref(), task_id.as_deref())
+}
@@ -0,0 +1,57 @@
+use crate::claude::index;
+use crate::claude::settings::{self, PermissionBehavior, PermissionRule, SettingsScope};
+use crate::claude::suggestions::{self, PermissionSuggestion};
+use crate::commands::projects::project_path;
+use crate::db::Database;
+use tauri::State;
+
+/// Get a project's merged permission rules, highest precedence first
+#[tauri::command]
+pub fn get_permission_rules(db: State<Database>, project_id: String) -> Result<Vec<PermissionRule>, String> {
+    settings::get_permission_rules(&project_path(&db, &project_id)?)
+}
+
+/// Add a permission rule to a settings file, returning the updated rules
+#[tauri::command]
+pub fn add_permission_rule(
+    db: State<Database>,
+    project_id: String,
+    scope: SettingsScope,
+    behavior: PermissionBehavior,
+    rule: String,
+) -> Result<Vec<PermissionRule>, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    settings::add_permission_rule(&project_path, scope, behavior, &rule)?;
+    settings::get_permission_rules(&project_path)
+}
+
+/// Remove a permission rule from a settings file, returning the updated rules
+#[tauri::command]
+pub fn remove_permission_rule(
+    db: State<Database>,
+    project_id: String,
+    scope: SettingsScope,
+    behavior: PermissionBehavior,
+    rule: String,
+) -> Result<Vec<PermissionRule>, String> {
+    let project_path = project_path(&db, &project_id)?;
+
+    settings::remove_permission_rule(&project_path, scope, behavior, &rule)?;
+    settings::get_permission_rules(&project_path)
+}
+
+/// Suggest permission rules for a project's frequent tool calls, flagging risky ones
+#[tauri::command]
+pub fn suggest_permission_rules(
+    db: State<Database>,
+    project_id: String,
+) -> Result<Vec<PermissionSuggestion>, String> {
+    let project_path = project_path(&db, &project_id)?;
+    let rules = settings::get_permission_rules(&project_path)?;
+
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+    index::refresh_project_if_present(&mut conn, &project_path)?;
+
+    suggestions::suggest_permission_rules(&conn, &project_path, &rules)
+}
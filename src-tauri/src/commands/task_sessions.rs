@@ -0,0 +1,83 @@
+use crate::claude::index;
+use crate::claude::task_sessions::{self, LinkedSession, SessionSuggestion, TaskUsage};
+use crate::commands::config::load_config;
+use crate::commands::costs::load_pricing;
+use crate::db::Database;
+use rusqlite::{params, Connection};
+use tauri::State;
+
+/// Index new session activity in the task's project
+fn refresh_task_project(conn: &mut Connection, task_id: &str) -> Result<(), String> {
+    let project_path: String = conn
+        .query_row(
+            "SELECT p.path FROM tasks t JOIN projects p ON p.id = t.project_id WHERE t.id = ?1",
+            params![task_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    index::refresh_project_if_present(conn, &project_path)
+}
+
+/// Get the sessions linked to a task with their cost
+#[tauri::command]
+pub fn get_task_sessions(db: State<Database>, task_id: String) -> Result<Vec<LinkedSession>, String> {
+    let pricing = load_pricing()?;
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    refresh_task_project(&mut conn, &task_id)?;
+    task_sessions::linked_sessions(&conn, &pricing, &task_id)
+}
+
+/// Link a session to a task, returning the task's linked sessions
+#[tauri::command]
+pub fn attach_task_session(
+    db: State<Database>,
+    task_id: String,
+    session_id: String,
+) -> Result<Vec<LinkedSession>, String> {
+    let pricing = load_pricing()?;
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    refresh_task_project(&mut conn, &task_id)?;
+    if index::get_session(&conn, &session_id)?.is_none() {
+        return Err("Session not found".to_string());
+    }
+
+    task_sessions::attach(&conn, &task_id, &session_id, "manual")?;
+    task_sessions::linked_sessions(&conn, &pricing, &task_id)
+}
+
+/// Unlink a session from a task, returning the task's linked sessions
+#[tauri::command]
+pub fn detach_task_session(
+    db: State<Database>,
+    task_id: String,
+    session_id: String,
+) -> Result<Vec<LinkedSession>, String> {
+    let pricing = load_pricing()?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    task_sessions::detach(&conn, &task_id, &session_id)?;
+    task_sessions::linked_sessions(&conn, &pricing, &task_id)
+}
+
+/// Get unlinked sessions whose first message matches a task, best match first
+#[tauri::command]
+pub fn suggest_task_sessions(db: State<Database>, task_id: String) -> Result<Vec<SessionSuggestion>, String> {
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    refresh_task_project(&mut conn, &task_id)?;
+    task_sessions::suggest(&conn, &task_id)
+}
+
+/// Get sessions, tokens, cost and time spent per task, optionally for a single project
+#[tauri::command]
+pub fn get_task_usage(db: State<Database>, project_id: Option<String>) -> Result<Vec<TaskUsage>, String> {
+    let pricing = load_pricing()?;
+    let idle_minutes = load_config()?.session_idle_minutes;
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    index::refresh_tracked_projects(&mut conn)?;
+    task_sessions::project_task_usage(&conn, &pricing, project_id.as_deref(), idle_minutes)
+}
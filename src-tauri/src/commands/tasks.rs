@@ -1,9 +1,11 @@
 use crate::db::Database;
 use crate::models::Task;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use tauri::State;
 use uuid::Uuid;
 
+pub const TASK_STATUSES: [&str; 4] = ["pending", "in_progress", "completed", "failed"];
+
 #[tauri::command]
 pub fn get_tasks(db: State<Database>, project_id: Option<String>) -> Result<Vec<Task>, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
@@ -58,22 +60,26 @@ pub fn add_task(
 ) -> Result<Task, String> {
     let conn = db.conn.lock().map_err(|e| e.to_string())?;
 
+    insert_task(&conn, &project_id, &content, "pending")
+}
+
+/// Create a task with a status
+pub fn insert_task(conn: &Connection, project_id: &str, content: &str, status: &str) -> Result<Task, String> {
     let id = Uuid::new_v4().to_string();
     let now = chrono::Utc::now().to_rfc3339();
-    let status = "pending".to_string();
 
     conn.execute(
         "INSERT INTO tasks (id, project_id, content, status, created_at, updated_at)
          VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        params![&id, &project_id, &content, &status, &now, &now],
+        params![&id, project_id, content, status, &now, &now],
     )
     .map_err(|e| e.to_string())?;
 
     Ok(Task {
         id,
-        project_id,
-        content,
-        status,
+        project_id: project_id.to_string(),
+        content: content.to_string(),
+        status: status.to_string(),
         created_at: now.clone(),
         updated_at: now,
     })
@@ -85,39 +91,46 @@ pub fn update_task_status(
     id: String,
     status: String,
 ) -> Result<Task, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    set_task_status(&conn, &id, &status)
+}
+
+/// Move a task to a status, returning the updated task
+pub fn set_task_status(conn: &Connection, id: &str, status: &str) -> Result<Task, String> {
     // Validate status
-    if !["pending", "in_progress", "completed"].contains(&status.as_str()) {
-        return Err("Invalid status. Must be 'pending', 'in_progress', or 'completed'".to_string());
+    if !TASK_STATUSES.contains(&status) {
+        return Err("Invalid status. Must be 'pending', 'in_progress', 'completed', or 'failed'".to_string());
     }
 
-    let conn = db.conn.lock().map_err(|e| e.to_string())?;
     let now = chrono::Utc::now().to_rfc3339();
 
     conn.execute(
         "UPDATE tasks SET status = ?1, updated_at = ?2 WHERE id = ?3",
-        params![&status, &now, &id],
+        params![status, &now, id],
     )
     .map_err(|e| e.to_string())?;
 
-    // Fetch and return updated task
-    let task = conn
-        .query_row(
-            "SELECT id, project_id, content, status, created_at, updated_at FROM tasks WHERE id = ?1",
-            params![&id],
-            |row| {
-                Ok(Task {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    content: row.get(2)?,
-                    status: row.get(3)?,
-                    created_at: row.get(4)?,
-                    updated_at: row.get(5)?,
-                })
-            },
-        )
-        .map_err(|e| e.to_string())?;
+    get_task(conn, id)
+}
 
-    Ok(task)
+/// Get a single task
+pub fn get_task(conn: &Connection, id: &str) -> Result<Task, String> {
+    conn.query_row(
+        "SELECT id, project_id, content, status, created_at, updated_at FROM tasks WHERE id = ?1",
+        params![id],
+        |row| {
+            Ok(Task {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                content: row.get(2)?,
+                status: row.get(3)?,
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+            })
+        },
+    )
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
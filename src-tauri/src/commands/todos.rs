@@ -0,0 +1,49 @@
+use crate::claude::index;
+use crate::claude::task_sessions;
+use crate::claude::todos::{self, SessionTodos, TodoItem};
+use crate::commands::projects::project_path;
+use crate::commands::tasks::{insert_task, TASK_STATUSES};
+use crate::db::Database;
+use crate::models::Task;
+use tauri::State;
+
+/// Get the latest todo list of each session in a project, marking items that are already tasks
+#[tauri::command]
+pub fn get_session_todos(db: State<Database>, project_id: String) -> Result<Vec<SessionTodos>, String> {
+    let project_path = project_path(&db, &project_id)?;
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    index::refresh_project_if_present(&mut conn, &project_path)?;
+    todos::session_todos(&conn, &project_id, &project_path)
+}
+
+/// Create tasks from todo items, skipping items a task already covers, and link them to the session
+#[tauri::command]
+pub fn import_todos(
+    db: State<Database>,
+    project_id: String,
+    session_id: String,
+    items: Vec<TodoItem>,
+) -> Result<Vec<Task>, String> {
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let existing = todos::task_contents(&tx, &project_id)?;
+    let mut tasks = vec![];
+
+    for item in todos::new_items(&existing, &items) {
+        let status = if TASK_STATUSES.contains(&item.status.as_str()) {
+            item.status.as_str()
+        } else {
+            "pending"
+        };
+
+        let task = insert_task(&tx, &project_id, &item.content, status)?;
+        task_sessions::attach(&tx, &task.id, &session_id, "todo")?;
+        tasks.push(task);
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(tasks)
+}
@@ -49,7 +49,17 @@ pub fn get_config_path() -> Result<PathBuf, String> {
     Ok(tusker_dir.join("config.json"))
 }
 
-fn init_schema(conn: &Connection) -> Result<()> {
+pub fn get_pricing_path() -> Result<PathBuf, String> {
+    let tusker_dir = get_tusker_dir()?;
+    Ok(tusker_dir.join("pricing.json"))
+}
+
+pub fn get_hook_endpoint_path() -> Result<PathBuf, String> {
+    let tusker_dir = get_tusker_dir()?;
+    Ok(tusker_dir.join("hook.json"))
+}
+
+pub(crate) fn init_schema(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         "
         CREATE TABLE IF NOT EXISTS projects (
@@ -72,6 +82,192 @@ fn init_schema(conn: &Connection) -> Result<()> {
 
         CREATE INDEX IF NOT EXISTS idx_tasks_project_id ON tasks(project_id);
         CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
+
+        CREATE TABLE IF NOT EXISTS session_files (
+            path TEXT PRIMARY KEY,
+            project_path TEXT NOT NULL,
+            size INTEGER NOT NULL DEFAULT 0,
+            mtime INTEGER NOT NULL DEFAULT 0,
+            offset INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE IF NOT EXISTS sessions (
+            id TEXT PRIMARY KEY,
+            project_path TEXT NOT NULL,
+            project_name TEXT NOT NULL,
+            first_message TEXT NOT NULL,
+            message_count INTEGER NOT NULL DEFAULT 0,
+            total_tokens INTEGER NOT NULL DEFAULT 0,
+            input_tokens INTEGER NOT NULL DEFAULT 0,
+            output_tokens INTEGER NOT NULL DEFAULT 0,
+            cache_read_input_tokens INTEGER NOT NULL DEFAULT 0,
+            cache_creation_input_tokens INTEGER NOT NULL DEFAULT 0,
+            service_tier TEXT,
+            error_count INTEGER NOT NULL DEFAULT 0,
+            model TEXT,
+            started_at TEXT NOT NULL,
+            last_activity TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS messages (
+            id INTEGER PRIMARY KEY,
+            uuid TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            parent_uuid TEXT,
+            msg_type TEXT NOT NULL,
+            content TEXT NOT NULL,
+            model TEXT,
+            input_tokens INTEGER,
+            output_tokens INTEGER,
+            cache_read_input_tokens INTEGER,
+            cache_creation_input_tokens INTEGER,
+            service_tier TEXT,
+            timestamp TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            line_offset INTEGER NOT NULL,
+            UNIQUE (session_id, uuid),
+            FOREIGN KEY (file_path) REFERENCES session_files(path) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_session_files_project_path ON session_files(project_path);
+        CREATE INDEX IF NOT EXISTS idx_sessions_project_path ON sessions(project_path);
+        CREATE INDEX IF NOT EXISTS idx_sessions_last_activity ON sessions(last_activity);
+        CREATE INDEX IF NOT EXISTS idx_messages_file_path ON messages(file_path);
+        CREATE INDEX IF NOT EXISTS idx_messages_uuid ON messages(uuid);
+        CREATE INDEX IF NOT EXISTS idx_messages_timestamp ON messages(timestamp);
+        CREATE INDEX IF NOT EXISTS idx_messages_session_timestamp ON messages(session_id, timestamp);
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+            body,
+            tokenize = 'porter unicode61'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+            DELETE FROM messages_fts WHERE rowid = old.id;
+        END;
+
+        CREATE TABLE IF NOT EXISTS tool_calls (
+            id TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            message_uuid TEXT NOT NULL,
+            name TEXT NOT NULL,
+            input TEXT NOT NULL,
+            is_error INTEGER,
+            timestamp TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            PRIMARY KEY (session_id, id),
+            FOREIGN KEY (file_path) REFERENCES session_files(path) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_tool_calls_file_path ON tool_calls(file_path);
+
+        CREATE TABLE IF NOT EXISTS session_errors (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            message_uuid TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            detail TEXT NOT NULL,
+            tool_use_id TEXT,
+            timestamp TEXT NOT NULL,
+            file_path TEXT NOT NULL,
+            notified INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (file_path) REFERENCES session_files(path) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_session_errors_session_id ON session_errors(session_id);
+        CREATE INDEX IF NOT EXISTS idx_session_errors_file_path ON session_errors(file_path);
+
+        CREATE TABLE IF NOT EXISTS budget_alerts (
+            date TEXT NOT NULL,
+            threshold INTEGER NOT NULL,
+            tokens_used INTEGER NOT NULL,
+            daily_token_limit INTEGER NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            PRIMARY KEY (date, threshold)
+        );
+
+        CREATE TABLE IF NOT EXISTS hook_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id TEXT,
+            event_name TEXT NOT NULL,
+            cwd TEXT,
+            tool_name TEXT,
+            payload TEXT NOT NULL,
+            received_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_hook_events_session_id ON hook_events(session_id);
+
+        CREATE TABLE IF NOT EXISTS approval_decisions (
+            id TEXT PRIMARY KEY,
+            session_id TEXT,
+            tool_name TEXT NOT NULL,
+            tool_input TEXT NOT NULL,
+            cwd TEXT,
+            decision TEXT NOT NULL,
+            decided_by TEXT NOT NULL,
+            reason TEXT,
+            requested_at TEXT NOT NULL,
+            decided_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_approval_decisions_session_id ON approval_decisions(session_id);
+
+        CREATE TABLE IF NOT EXISTS memory_versions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            path TEXT NOT NULL,
+            content TEXT NOT NULL,
+            source TEXT NOT NULL,
+            saved_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_memory_versions_path ON memory_versions(path);
+
+        CREATE TABLE IF NOT EXISTS task_runs (
+            id TEXT PRIMARY KEY,
+            task_id TEXT NOT NULL,
+            project_id TEXT NOT NULL,
+            status TEXT NOT NULL,
+            process_id TEXT,
+            session_id TEXT,
+            result TEXT,
+            error TEXT,
+            cost_usd REAL,
+            duration_ms INTEGER,
+            num_turns INTEGER,
+            queued_at TEXT NOT NULL,
+            started_at TEXT,
+            ended_at TEXT,
+            FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_task_runs_task_id ON task_runs(task_id);
+
+        CREATE TABLE IF NOT EXISTS task_sessions (
+            task_id TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            source TEXT NOT NULL,
+            linked_at TEXT NOT NULL,
+            PRIMARY KEY (task_id, session_id),
+            FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_task_sessions_session_id ON task_sessions(session_id);
+
+        CREATE TABLE IF NOT EXISTS notifications (
+            id TEXT PRIMARY KEY,
+            type TEXT NOT NULL,
+            title TEXT NOT NULL,
+            message TEXT NOT NULL,
+            project_id TEXT,
+            session_id TEXT,
+            message_uuid TEXT,
+            read INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_notifications_created_at ON notifications(created_at);
         "
     )?;
 
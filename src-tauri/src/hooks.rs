@@ -0,0 +1,441 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+use crate::approvals;
+use crate::claude::settings::{read_settings, write_settings, SettingsScope};
+use crate::commands::config::load_config;
+use crate::db::{get_hook_endpoint_path, Database};
+use crate::notifications;
+
+/// Claude Code hook events forwarded to Tusker
+pub const HOOK_EVENTS: [&str; 4] = ["PreToolUse", "PostToolUse", "Notification", "Stop"];
+
+/// Seconds Claude Code waits on the PreToolUse hook, above the longest approval timeout
+const APPROVAL_HOOK_TIMEOUT_SECS: u32 = 600;
+
+/// Hook events that take a tool matcher
+const TOOL_HOOK_EVENTS: [&str; 2] = ["PreToolUse", "PostToolUse"];
+
+/// Name of the helper binary hooks invoke, bundled as a sidecar
+const HOOK_BINARY: &str = "tusker-hook";
+
+/// Largest hook payload accepted
+const MAX_BODY: usize = 10 * 1024 * 1024;
+
+/// Time allowed for a hook to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where a running Tusker listens for hook events, written to `~/.tusker/hook.json`
+///
+/// Only the current user may read it, as the token lets anyone post events and
+/// approval decisions.
+#[derive(Debug, Serialize, Deserialize)]
+struct HookEndpoint {
+    port: u16,
+    token: String,
+}
+
+/// A hook event received from Claude Code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookEvent {
+    pub id: i64,
+    pub session_id: Option<String>,
+    pub event_name: String,
+    pub cwd: Option<String>,
+    pub tool_name: Option<String>,
+    pub payload: Value, // The JSON Claude Code passed to the hook
+    pub received_at: String,
+}
+
+/// Listen for hook events on 127.0.0.1 and store them as they arrive
+///
+/// The port and a bearer token are written to `~/.tusker/hook.json` for the
+/// `tusker-hook` helper until `stop` removes it. Every event is stored in `hook_events` and emitted as
+/// `hook-event`; `Notification` events also raise a notification.
+pub fn start(app: AppHandle) -> Result<(), String> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .map_err(|e| format!("Failed to bind hook listener: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| e.to_string())?
+        .port();
+
+    let endpoint = HookEndpoint {
+        port,
+        token: Uuid::new_v4().to_string(),
+    };
+    write_endpoint(&get_hook_endpoint_path()?, &endpoint)
+        .map_err(|e| format!("Failed to write hook.json: {}", e))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().filter_map(|s| s.ok()) {
+            let app = app.clone();
+            let token = endpoint.token.clone();
+            std::thread::spawn(move || handle_connection(&app, stream, &token));
+        }
+    });
+
+    Ok(())
+}
+
+/// Remove `~/.tusker/hook.json`, so hooks stop reaching for a closed app
+pub fn stop() {
+    if let Ok(path) = get_hook_endpoint_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Write the endpoint readable by the current user only
+fn write_endpoint(path: &Path, endpoint: &HookEndpoint) -> std::io::Result<()> {
+    let content = serde_json::to_string_pretty(endpoint)?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path)?;
+    // The mode only applies to new files, tighten one left by an older version
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(content.as_bytes())
+}
+
+/// Serve a single `POST /hook` request
+fn handle_connection(app: &AppHandle, mut stream: TcpStream, token: &str) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+    let (status, body) = match read_request(&stream, token) {
+        Ok(payload) => match receive(app, payload) {
+            Ok(reply) => ("200 OK", reply),
+            Err(e) => ("500 Internal Server Error", json!({ "error": e })),
+        },
+        Err((status, e)) => (status, json!({ "error": e })),
+    };
+
+    let body = body.to_string();
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+}
+
+/// Read an authorized hook request and parse its JSON body
+fn read_request(stream: &TcpStream, token: &str) -> Result<Value, (&'static str, String)> {
+    let bad_request = |e: String| ("400 Bad Request", e);
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .map_err(|e| bad_request(e.to_string()))?;
+    if !request_line.starts_with("POST /hook ") {
+        return Err(("404 Not Found", "Expected POST /hook".to_string()));
+    }
+
+    let mut content_length = 0;
+    let mut authorized = false;
+    loop {
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .map_err(|e| bad_request(e.to_string()))?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            match name.to_ascii_lowercase().as_str() {
+                "content-length" => {
+                    content_length = value
+                        .parse()
+                        .map_err(|_| bad_request("Invalid Content-Length".to_string()))?
+                }
+                "authorization" => authorized = value == format!("Bearer {}", token),
+                _ => {}
+            }
+        }
+    }
+
+    if !authorized {
+        return Err(("401 Unauthorized", "Invalid hook token".to_string()));
+    }
+    if content_length > MAX_BODY {
+        return Err(("413 Payload Too Large", "Hook payload too large".to_string()));
+    }
+
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| bad_request(e.to_string()))?;
+
+    serde_json::from_slice(&body).map_err(|e| bad_request(format!("Invalid hook payload: {}", e)))
+}
+
+/// Store a hook event, tell the frontend, and compute the reply for the hook
+fn receive(app: &AppHandle, payload: Value) -> Result<Value, String> {
+    let event = {
+        let db = app.state::<Database>();
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        store_event(&conn, payload)?
+    };
+
+    let _ = app.emit("hook-event", &event);
+
+    if event.event_name == "Notification" {
+        notify(app, &event);
+    }
+
+    if event.event_name == "PreToolUse" {
+        let config = load_config()?;
+        if config.approvals_enabled {
+            return approvals::request(app, &event, &config);
+        }
+    }
+
+    Ok(json!({}))
+}
+
+/// Insert a hook payload into `hook_events`
+fn store_event(conn: &Connection, payload: Value) -> Result<HookEvent, String> {
+    let field = |name: &str| payload.get(name).and_then(|v| v.as_str()).map(String::from);
+
+    let session_id = field("session_id");
+    let event_name = field("hook_event_name").unwrap_or_else(|| "Unknown".to_string());
+    let cwd = field("cwd");
+    let tool_name = field("tool_name");
+    let received_at = chrono::Utc::now().to_rfc3339();
+
+    conn.execute(
+        "INSERT INTO hook_events (session_id, event_name, cwd, tool_name, payload, received_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![&session_id, &event_name, &cwd, &tool_name, payload.to_string(), &received_at],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(HookEvent {
+        id: conn.last_insert_rowid(),
+        session_id,
+        event_name,
+        cwd,
+        tool_name,
+        payload,
+        received_at,
+    })
+}
+
+/// Raise Claude's own notification (e.g. a permission prompt) in Tusker
+fn notify(app: &AppHandle, event: &HookEvent) {
+    if !load_config().map(|c| c.session_notifications).unwrap_or(true) {
+        return;
+    }
+
+    let message = event
+        .payload
+        .get("message")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Claude needs your attention");
+
+    let project_id = event.cwd.as_deref().and_then(|cwd| {
+        let db = app.state::<Database>();
+        let conn = db.conn.lock().ok()?;
+        notifications::project_id_for_path(&conn, cwd)
+    });
+
+    let _ = notifications::raise(
+        app,
+        "info",
+        "Claude Code",
+        message,
+        project_id.as_deref(),
+        event.session_id.as_deref(),
+        None,
+    );
+}
+
+/// Recent hook events, newest first, optionally for one session
+pub fn list_events(
+    conn: &Connection,
+    session_id: Option<&str>,
+    limit: u32,
+) -> Result<Vec<HookEvent>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, session_id, event_name, cwd, tool_name, payload, received_at
+             FROM hook_events
+             WHERE ?1 IS NULL OR session_id = ?1
+             ORDER BY id DESC
+             LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let events = stmt
+        .query_map(params![session_id, limit], |row| {
+            let payload: String = row.get(5)?;
+
+            Ok(HookEvent {
+                id: row.get(0)?,
+                session_id: row.get(1)?,
+                event_name: row.get(2)?,
+                cwd: row.get(3)?,
+                tool_name: row.get(4)?,
+                payload: serde_json::from_str(&payload).unwrap_or(Value::Null),
+                received_at: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(events)
+}
+
+/// Path of the `tusker-hook` sidecar, which Tauri places next to the app executable
+pub fn hook_binary_path() -> Result<PathBuf, String> {
+    let exe = tauri::utils::platform::current_exe().map_err(|e| e.to_string())?;
+    let helper = exe.with_file_name(format!("{}{}", HOOK_BINARY, std::env::consts::EXE_SUFFIX));
+
+    if !helper.exists() {
+        return Err(format!("{} not found at {}", HOOK_BINARY, helper.display()));
+    }
+
+    Ok(helper)
+}
+
+/// Quote a path for the shell Claude Code runs hook commands in
+fn shell_quote(path: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", path)
+    } else {
+        format!("'{}'", path.replace('\'', "'\\''"))
+    }
+}
+
+/// Add a hook running `helper` for every forwarded event to a settings JSON object
+///
+/// Events that already run the helper are left alone, so installing twice is
+/// harmless, except that hooks installed with an unquoted path get quoted.
+/// Returns whether anything changed.
+fn add_hook_entries(settings: &mut Value, helper: &str) -> Result<bool, String> {
+    let command = shell_quote(helper);
+    let root = settings
+        .as_object_mut()
+        .ok_or("settings.json is not a JSON object")?;
+    let hooks = root
+        .entry("hooks")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .ok_or("\"hooks\" in settings.json is not an object")?;
+
+    let mut changed = false;
+
+    for event in HOOK_EVENTS {
+        let entries = hooks
+            .entry(event)
+            .or_insert_with(|| json!([]))
+            .as_array_mut()
+            .ok_or_else(|| format!("\"hooks.{}\" in settings.json is not an array", event))?;
+
+        // PreToolUse may wait on an approval, so it needs a longer timeout than the default
+        let timeout = (event == "PreToolUse").then(|| json!(APPROVAL_HOOK_TIMEOUT_SECS));
+
+        let installed = entries
+            .iter_mut()
+            .filter_map(|entry| entry["hooks"].as_array_mut())
+            .flatten()
+            .find(|h| h["command"] == command || h["command"] == helper);
+        if let Some(hook) = installed {
+            if hook["command"] != command {
+                hook["command"] = json!(command);
+                changed = true;
+            }
+            if let Some(timeout) = timeout.filter(|t| hook["timeout"] != *t) {
+                hook["timeout"] = timeout;
+                changed = true;
+            }
+            continue;
+        }
+
+        let mut hook = json!({ "type": "command", "command": command });
+        if let Some(timeout) = timeout {
+            hook["timeout"] = timeout;
+        }
+        let mut entry = json!({ "hooks": [hook] });
+        if TOOL_HOOK_EVENTS.contains(&event) {
+            entry["matcher"] = json!("*");
+        }
+        entries.push(entry);
+        changed = true;
+    }
+
+    Ok(changed)
+}
+
+/// Install Tusker's hook entries into one of a project's settings files
+///
+/// The helper path is specific to this machine, so it belongs in the personal
+/// `Local` scope rather than the shared one. Returns the path of the settings file.
+pub fn install_hooks(project_path: &str, scope: SettingsScope) -> Result<PathBuf, String> {
+    let helper = hook_binary_path()?.to_string_lossy().to_string();
+    let settings_path = scope.path(project_path)?;
+
+    let mut settings = read_settings(&settings_path)?;
+
+    if add_hook_entries(&mut settings, &helper)? {
+        write_settings(&settings_path, &settings)?;
+    }
+
+    Ok(settings_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_hook_entries_keeps_existing_hooks() {
+        let mut settings = json!({
+            "permissions": { "allow": ["Bash(npm test)"] },
+            "hooks": { "Stop": [{ "hooks": [{ "type": "command", "command": "say done" }] }] }
+        });
+
+        assert!(add_hook_entries(&mut settings, "/opt/tusker-hook").unwrap());
+        assert!(!add_hook_entries(&mut settings, "/opt/tusker-hook").unwrap());
+
+        assert_eq!(settings["permissions"]["allow"][0], "Bash(npm test)");
+        assert_eq!(settings["hooks"]["Stop"][1]["hooks"][0]["command"], shell_quote("/opt/tusker-hook"));
+        assert_eq!(settings["hooks"]["Stop"].as_array().unwrap().len(), 2);
+        assert_eq!(settings["hooks"]["PreToolUse"][0]["matcher"], "*");
+        assert_eq!(settings["hooks"]["PreToolUse"][0]["hooks"][0]["timeout"], 600);
+        assert!(settings["hooks"]["Notification"][0].get("matcher").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hook_command_quotes_the_helper_path() {
+        let helper = "/Applications/Tusker App.app/Contents/MacOS/tusker-hook";
+        assert_eq!(shell_quote(helper), format!("'{}'", helper));
+        assert_eq!(shell_quote("/opt/it's/tusker-hook"), "'/opt/it'\\''s/tusker-hook'");
+
+        // Hooks installed before the path was quoted are updated in place
+        let mut settings = json!({
+            "hooks": { "Stop": [{ "hooks": [{ "type": "command", "command": helper }] }] }
+        });
+        assert!(add_hook_entries(&mut settings, helper).unwrap());
+        assert_eq!(settings["hooks"]["Stop"].as_array().unwrap().len(), 1);
+        assert_eq!(settings["hooks"]["Stop"][0]["hooks"][0]["command"], shell_quote(helper));
+    }
+}
@@ -0,0 +1,426 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::models::Config;
+
+pub const PERMISSION_MODES: [&str; 4] = ["default", "acceptEdits", "plan", "bypassPermissions"];
+
+/// How often a running process is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How long to wait for remaining output after exit, as children of the process may hold its pipes open
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How to start a Claude session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaunchOptions {
+    pub prompt: Option<String>,
+    pub resume: Option<String>, // Session id passed to `--resume`
+    pub model: Option<String>,
+    pub permission_mode: Option<String>, // One of PERMISSION_MODES
+}
+
+/// A `claude` process started by Tusker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeProcess {
+    pub id: String,
+    pub project_id: String,
+    pub cwd: String,
+    pub pid: u32,
+    pub args: Vec<String>,
+    pub status: String, // "running", "exited" or "stopped"
+    pub exit_code: Option<i32>,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+}
+
+/// A line a process wrote, emitted as `claude-output`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessOutput {
+    pub process_id: String,
+    pub stream: String, // "stdout" or "stderr"
+    pub line: String,
+}
+
+/// What a process reports while it runs
+pub enum ProcessEvent {
+    Output(ProcessOutput),
+    Exited(ClaudeProcess),
+}
+
+pub type EventSink = Arc<dyn Fn(ProcessEvent) + Send + Sync>;
+
+struct Tracked {
+    process: ClaudeProcess,
+    child: Option<Child>, // Taken once the process has exited
+}
+
+/// Processes started from the app, running and finished
+#[derive(Default)]
+pub struct Launcher {
+    processes: Arc<Mutex<HashMap<String, Tracked>>>,
+}
+
+impl Launcher {
+    /// Processes started since the app launched, newest first
+    pub fn list(&self) -> Result<Vec<ClaudeProcess>, String> {
+        let processes = self.processes.lock().map_err(|e| e.to_string())?;
+
+        let mut list: Vec<ClaudeProcess> = processes.values().map(|t| t.process.clone()).collect();
+        list.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+
+        Ok(list)
+    }
+
+    /// Start the CLI in a directory, reporting output and exit to `sink`
+    pub fn spawn(
+        &self,
+        cli: &str,
+        args: Vec<String>,
+        project_id: &str,
+        cwd: &str,
+        sink: EventSink,
+    ) -> Result<ClaudeProcess, String> {
+        if !Path::new(cwd).is_dir() {
+            return Err(format!("Project directory {} does not exist", cwd));
+        }
+
+        let mut command = Command::new(cli);
+        command
+            .args(&args)
+            .current_dir(cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Own process group, so stopping also ends the tools and MCP servers it started
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {}", cli, e))?;
+
+        let process = ClaudeProcess {
+            id: Uuid::new_v4().to_string(),
+            project_id: project_id.to_string(),
+            cwd: cwd.to_string(),
+            pid: child.id(),
+            args,
+            status: "running".to_string(),
+            exit_code: None,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            ended_at: None,
+        };
+
+        let (done_tx, done_rx) = mpsc::channel();
+        let mut readers = 0;
+        if let Some(out) = child.stdout.take() {
+            forward(out, "stdout", &process.id, &sink, done_tx.clone());
+            readers += 1;
+        }
+        if let Some(err) = child.stderr.take() {
+            forward(err, "stderr", &process.id, &sink, done_tx);
+            readers += 1;
+        }
+
+        self.processes.lock().map_err(|e| e.to_string())?.insert(
+            process.id.clone(),
+            Tracked {
+                process: process.clone(),
+                child: Some(child),
+            },
+        );
+
+        let processes = Arc::clone(&self.processes);
+        let id = process.id.clone();
+        thread::spawn(move || {
+            let exited = wait(&processes, &id);
+            // Report exit after the last line of output
+            let deadline = Instant::now() + DRAIN_TIMEOUT;
+            for _ in 0..readers {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if done_rx.recv_timeout(left).is_err() {
+                    break;
+                }
+            }
+            if let Some(process) = exited {
+                sink(ProcessEvent::Exited(process));
+            }
+        });
+
+        Ok(process)
+    }
+
+    /// Kill a running process
+    pub fn stop(&self, id: &str) -> Result<(), String> {
+        let mut processes = self.processes.lock().map_err(|e| e.to_string())?;
+
+        let tracked = processes.get_mut(id).ok_or("Process not found")?;
+        let child = tracked.child.as_mut().ok_or("Process has already exited")?;
+
+        if !terminate_group(child.id()) {
+            child.kill().map_err(|e| format!("Failed to stop process: {}", e))?;
+        }
+        tracked.process.status = "stopped".to_string();
+
+        Ok(())
+    }
+
+    /// Kill every running process, as their process groups outlive the app
+    pub fn stop_all(&self) {
+        let Ok(processes) = self.processes.lock() else {
+            return;
+        };
+
+        for child in processes.values().filter_map(|t| t.child.as_ref()) {
+            terminate_group(child.id());
+        }
+    }
+}
+
+/// Ask a process group to terminate, letting Claude clean up
+#[cfg(unix)]
+fn terminate_group(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-TERM", "--", &format!("-{}", pid)])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(unix))]
+fn terminate_group(_pid: u32) -> bool {
+    false
+}
+
+/// Send each line of a pipe to the sink until it closes
+fn forward<R: Read + Send + 'static>(
+    pipe: R,
+    stream: &str,
+    process_id: &str,
+    sink: &EventSink,
+    done: mpsc::Sender<()>,
+) {
+    let stream = stream.to_string();
+    let process_id = process_id.to_string();
+    let sink = Arc::clone(sink);
+
+    thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+            sink(ProcessEvent::Output(ProcessOutput {
+                process_id: process_id.clone(),
+                stream: stream.clone(),
+                line,
+            }));
+        }
+        let _ = done.send(());
+    });
+}
+
+/// Poll a process until it exits, recording how it ended
+fn wait(processes: &Mutex<HashMap<String, Tracked>>, id: &str) -> Option<ClaudeProcess> {
+    loop {
+        {
+            let mut processes = processes.lock().ok()?;
+            let tracked = processes.get_mut(id)?;
+
+            match tracked.child.as_mut()?.try_wait() {
+                Ok(None) => {}
+                result => {
+                    tracked.child = None;
+                    tracked.process.exit_code = result.ok().flatten().and_then(|s| s.code());
+                    tracked.process.ended_at = Some(chrono::Utc::now().to_rfc3339());
+                    if tracked.process.status == "running" {
+                        tracked.process.status = "exited".to_string();
+                    }
+                    return Some(tracked.process.clone());
+                }
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Command line arguments for an interactive session
+pub fn claude_args(options: &LaunchOptions) -> Result<Vec<String>, String> {
+    let mut args = vec![];
+
+    if let Some(resume) = options.resume.as_deref().filter(|r| !r.is_empty()) {
+        args.extend(["--resume".to_string(), resume.to_string()]);
+    }
+
+    if let Some(model) = options.model.as_deref().filter(|m| !m.is_empty()) {
+        if model.chars().any(char::is_whitespace) {
+            return Err(format!("Invalid model '{}'", model));
+        }
+        args.extend(["--model".to_string(), model.to_string()]);
+    }
+
+    if let Some(mode) = options.permission_mode.as_deref() {
+        if !PERMISSION_MODES.contains(&mode) {
+            return Err(format!(
+                "Invalid permission mode. Must be one of {}",
+                PERMISSION_MODES.join(", ")
+            ));
+        }
+        args.extend(["--permission-mode".to_string(), mode.to_string()]);
+    }
+
+    // Last, so a prompt starting with '-' isn't read as an option
+    if let Some(prompt) = options.prompt.as_deref().filter(|p| !p.trim().is_empty()) {
+        args.extend(["--".to_string(), prompt.to_string()]);
+    }
+
+    Ok(args)
+}
+
+/// Path of the CLI, `claude` from PATH unless configured
+pub fn cli_path(config: &Config) -> String {
+    config
+        .claude_cli_path
+        .clone()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "claude".to_string())
+}
+
+/// Start a Claude session in a project, reporting its output and exit to `sink`
+fn start(
+    launcher: &Launcher,
+    config: &Config,
+    project_id: &str,
+    project_path: &str,
+    options: &LaunchOptions,
+    sink: EventSink,
+) -> Result<ClaudeProcess, String> {
+    let args = claude_args(options)?;
+
+    launcher.spawn(&cli_path(config), args, project_id, project_path, sink)
+}
+
+/// Start a Claude session in a project
+///
+/// Emits `claude-output` with each `ProcessOutput` line and `claude-exited`
+/// with the final `ClaudeProcess`.
+pub fn launch(
+    app: &AppHandle,
+    launcher: &Launcher,
+    config: &Config,
+    project_id: &str,
+    project_path: &str,
+    options: &LaunchOptions,
+) -> Result<ClaudeProcess, String> {
+    let app = app.clone();
+    let sink: EventSink = Arc::new(move |event| match event {
+        ProcessEvent::Output(output) => {
+            let _ = app.emit("claude-output", &output);
+        }
+        ProcessEvent::Exited(process) => {
+            let _ = app.emit("claude-exited", &process);
+        }
+    });
+
+    start(launcher, config, project_id, project_path, options, sink)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claude_args() {
+        let options = LaunchOptions {
+            prompt: Some("-fix the build".to_string()),
+            resume: Some("abc-123".to_string()),
+            model: Some("sonnet".to_string()),
+            permission_mode: Some("acceptEdits".to_string()),
+        };
+
+        assert_eq!(
+            claude_args(&options).unwrap(),
+            [
+                "--resume", "abc-123", "--model", "sonnet", "--permission-mode", "acceptEdits", "--",
+                "-fix the build",
+            ]
+        );
+        assert!(claude_args(&LaunchOptions::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_claude_args_rejects_invalid_options() {
+        let mode = LaunchOptions {
+            permission_mode: Some("yolo".to_string()),
+            ..Default::default()
+        };
+        let model = LaunchOptions {
+            model: Some("sonnet --dangerously-skip-permissions".to_string()),
+            ..Default::default()
+        };
+
+        assert!(claude_args(&mode).is_err());
+        assert!(claude_args(&model).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_launch_streams_output_and_stops() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // A stub `claude` on PATH that echoes its arguments, then waits to be stopped
+        let dir = std::env::temp_dir().join(format!("tusker-launch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let stub = dir.join("claude");
+        std::fs::write(&stub, "#!/bin/sh\necho \"args: $*\"\necho warming up >&2\nexec sleep 30\n").unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let paths = std::iter::once(dir.clone()).chain(std::env::split_paths(&path));
+        std::env::set_var("PATH", std::env::join_paths(paths).unwrap());
+
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let sink: EventSink = Arc::new(move |event| {
+            let _ = tx.lock().unwrap().send(event);
+        });
+        let options = LaunchOptions {
+            model: Some("sonnet".to_string()),
+            ..Default::default()
+        };
+
+        let launcher = Launcher::default();
+        let process = start(&launcher, &Config::default(), "p1", dir.to_str().unwrap(), &options, sink).unwrap();
+        assert_eq!(process.status, "running");
+        assert_eq!(launcher.list().unwrap().len(), 1);
+
+        let mut lines = vec![];
+        while lines.len() < 2 {
+            match rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                ProcessEvent::Output(output) => lines.push((output.stream, output.line)),
+                ProcessEvent::Exited(_) => panic!("exited before stopping"),
+            }
+        }
+        lines.sort();
+        assert_eq!(
+            lines,
+            [
+                ("stderr".to_string(), "warming up".to_string()),
+                ("stdout".to_string(), "args: --model sonnet".to_string()),
+            ]
+        );
+
+        launcher.stop(&process.id).unwrap();
+        let exited = loop {
+            if let ProcessEvent::Exited(process) = rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                break process;
+            }
+        };
+        assert_eq!(exited.status, "stopped");
+        assert!(launcher.stop(&process.id).is_err());
+    }
+}
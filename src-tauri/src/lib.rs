@@ -1,19 +1,46 @@
+mod approvals;
+mod budget;
 mod commands;
 mod db;
+mod hooks;
+mod launcher;
+mod lifecycle;
 mod models;
+mod notifications;
+mod runner;
 mod claude;
+mod watcher;
 
 use db::Database;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Initialize database
     let database = Database::new().expect("Failed to initialize database");
 
+    // Task runs die with the app, give their tasks back
+    if let Err(e) = database.conn.lock().map_err(|e| e.to_string()).and_then(|conn| runner::recover(&conn)) {
+        eprintln!("Failed to recover task runs: {}", e);
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(database)
+        .manage(approvals::Approvals::default())
+        .manage(launcher::Launcher::default())
+        .manage(runner::TaskRunner::default())
+        .setup(|app| {
+            // Live session updates and hooks are optional, the app works without them
+            if let Err(e) = watcher::start(app.handle().clone()) {
+                eprintln!("Session watcher disabled: {}", e);
+            }
+            if let Err(e) = hooks::start(app.handle().clone()) {
+                eprintln!("Hook receiver disabled: {}", e);
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Project commands
             commands::projects::get_projects,
@@ -33,7 +60,80 @@ pub fn run() {
             commands::claude::get_project_sessions,
             commands::claude::get_recent_sessions,
             commands::claude::get_session_messages,
+            commands::claude::search_sessions,
+            commands::claude::get_session_errors,
+            // Cost commands
+            commands::costs::get_pricing,
+            commands::costs::get_message_costs,
+            commands::costs::get_session_cost,
+            commands::costs::get_project_costs,
+            commands::costs::get_daily_costs,
+            // Budget commands
+            commands::budget::get_budget_status,
+            // Hook commands
+            commands::hooks::get_hook_events,
+            commands::hooks::install_hooks,
+            // Approval commands
+            commands::approvals::get_pending_approvals,
+            commands::approvals::resolve_approval,
+            commands::approvals::get_approval_audit,
+            // Settings commands
+            commands::settings::get_permission_rules,
+            commands::settings::add_permission_rule,
+            commands::settings::remove_permission_rule,
+            commands::settings::suggest_permission_rules,
+            // Memory commands
+            commands::memory::get_memory_files,
+            commands::memory::read_memory_file,
+            commands::memory::save_memory_file,
+            commands::memory::get_memory_versions,
+            commands::memory::get_memory_version_content,
+            commands::memory::diff_memory_version,
+            commands::memory::restore_memory_version,
+            commands::memory::lint_project_memory,
+            // Slash command and agent commands
+            commands::custom::get_custom_files,
+            commands::custom::create_custom_file,
+            commands::custom::update_custom_file,
+            commands::custom::rename_custom_file,
+            commands::custom::delete_custom_file,
+            // MCP server commands
+            commands::mcp::get_mcp_servers,
+            commands::mcp::add_mcp_server,
+            commands::mcp::update_mcp_server,
+            commands::mcp::remove_mcp_server,
+            commands::mcp::set_mcp_server_enabled,
+            // Launch commands
+            commands::launcher::launch_claude,
+            commands::launcher::stop_claude,
+            commands::launcher::get_claude_processes,
+            // Task runner commands
+            commands::runner::run_task,
+            commands::runner::run_project_tasks,
+            commands::runner::cancel_task_run,
+            commands::runner::get_task_runs,
+            // Task session commands
+            commands::task_sessions::get_task_sessions,
+            commands::task_sessions::attach_task_session,
+            commands::task_sessions::detach_task_session,
+            commands::task_sessions::suggest_task_sessions,
+            commands::task_sessions::get_task_usage,
+            // Todo import commands
+            commands::todos::get_session_todos,
+            commands::todos::import_todos,
+            // Notification commands
+            commands::notifications::get_notifications,
+            commands::notifications::mark_notification_read,
+            commands::notifications::mark_all_notifications_read,
+            commands::notifications::delete_notification,
+            commands::notifications::clear_notifications,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                hooks::stop();
+                app.state::<launcher::Launcher>().stop_all();
+            }
+        });
 }
@@ -0,0 +1,177 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::claude::{ClaudeMessage, ClaudeSession};
+
+/// How long a quiet session is remembered before it is forgotten
+const FORGET_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Lifecycle state of a live session
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionState {
+    Started,
+    Active,
+    Idle,     // No writes for a while, mid-turn (e.g. waiting on a permission prompt)
+    Finished, // Claude ended its turn and is waiting for the user
+}
+
+/// A lifecycle transition of a session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionLifecycleEvent {
+    pub session: ClaudeSession,
+    pub state: SessionState,
+    pub sound: bool, // Whether the frontend should play a sound, per `Config.sound_enabled`
+}
+
+struct TrackedSession {
+    session: ClaudeSession,
+    state: SessionState,
+    last_write: Instant,
+    turn_ended: bool, // Last message is an assistant message with stop_reason end_turn
+}
+
+/// Tracks sessions written to while Tusker runs and detects when they go quiet
+#[derive(Default)]
+pub struct SessionTracker {
+    sessions: HashMap<String, TrackedSession>,
+    seen: HashSet<String>, // Every session written to, kept after the session is forgotten
+}
+
+impl SessionTracker {
+    /// Record the latest summary of a session that was written to
+    ///
+    /// Returns a `Started` event the first time the session is seen, however
+    /// it got into the index.
+    pub fn updated(&mut self, session: &ClaudeSession, now: Instant) -> Option<SessionLifecycleEvent> {
+        let started = self.seen.insert(session.id.clone()).then(|| SessionLifecycleEvent {
+            session: session.clone(),
+            state: SessionState::Started,
+            sound: false,
+        });
+
+        let tracked = self
+            .sessions
+            .entry(session.id.clone())
+            .or_insert_with(|| TrackedSession {
+                session: session.clone(),
+                state: SessionState::Active,
+                last_write: now,
+                turn_ended: false,
+            });
+
+        tracked.session = session.clone();
+        tracked.state = SessionState::Active;
+        tracked.last_write = now;
+
+        started
+    }
+
+    /// Record a message appended to a session, in file order
+    pub fn message(&mut self, msg: &ClaudeMessage) {
+        if let Some(tracked) = self.sessions.get_mut(&msg.session_id) {
+            tracked.turn_ended =
+                msg.msg_type == "assistant" && msg.stop_reason.as_deref() == Some("end_turn");
+        }
+    }
+
+    /// Sessions that have had no writes for `quiet_for` since they were last active
+    pub fn tick(&mut self, now: Instant, quiet_for: Duration) -> Vec<SessionLifecycleEvent> {
+        self.sessions
+            .retain(|_, s| now.saturating_duration_since(s.last_write) < FORGET_AFTER);
+
+        self.sessions
+            .values_mut()
+            .filter(|s| {
+                s.state == SessionState::Active
+                    && now.saturating_duration_since(s.last_write) >= quiet_for
+            })
+            .map(|s| {
+                s.state = if s.turn_ended {
+                    SessionState::Finished
+                } else {
+                    SessionState::Idle
+                };
+
+                SessionLifecycleEvent {
+                    session: s.session.clone(),
+                    state: s.state,
+                    sound: false,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str) -> ClaudeSession {
+        ClaudeSession {
+            id: id.to_string(),
+            project_path: "/work/foo".to_string(),
+            project_name: "foo".to_string(),
+            first_message: String::new(),
+            message_count: 0,
+            total_tokens: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_read_input_tokens: 0,
+            cache_creation_input_tokens: 0,
+            cache_hit_ratio: 0.0,
+            error_count: 0,
+            service_tier: None,
+            model: None,
+            started_at: String::new(),
+            last_activity: String::new(),
+        }
+    }
+
+    fn assistant(session_id: &str, stop_reason: &str) -> ClaudeMessage {
+        ClaudeMessage {
+            uuid: "u1".to_string(),
+            parent_uuid: None,
+            session_id: session_id.to_string(),
+            msg_type: "assistant".to_string(),
+            content: String::new(),
+            blocks: vec![],
+            model: None,
+            input_tokens: None,
+            output_tokens: None,
+            cache_read_input_tokens: None,
+            cache_creation_input_tokens: None,
+            service_tier: None,
+            stop_reason: Some(stop_reason.to_string()),
+            is_api_error: false,
+            timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_quiet_sessions_finish_or_go_idle() {
+        let start = Instant::now();
+        let quiet_for = Duration::from_secs(120);
+        let mut tracker = SessionTracker::default();
+
+        assert_eq!(tracker.updated(&session("done"), start).unwrap().state, SessionState::Started);
+        tracker.message(&assistant("done", "end_turn"));
+        assert!(tracker.updated(&session("stuck"), start).is_some());
+        tracker.message(&assistant("stuck", "tool_use"));
+
+        assert!(tracker.tick(start + Duration::from_secs(60), quiet_for).is_empty());
+
+        let mut events = tracker.tick(start + quiet_for, quiet_for);
+        events.sort_by(|a, b| a.session.id.cmp(&b.session.id));
+        assert_eq!(events[0].session.id, "done");
+        assert_eq!(events[0].state, SessionState::Finished);
+        assert_eq!(events[1].session.id, "stuck");
+        assert_eq!(events[1].state, SessionState::Idle);
+
+        // Each quiet period is reported once, until the session is written to again
+        assert!(tracker.tick(start + quiet_for * 2, quiet_for).is_empty());
+        assert!(tracker.updated(&session("stuck"), start + quiet_for * 2).is_none());
+        assert_eq!(tracker.tick(start + quiet_for * 3, quiet_for).len(), 1);
+    }
+}
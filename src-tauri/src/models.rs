@@ -14,11 +14,25 @@ pub struct Task {
     pub id: String,
     pub project_id: String,
     pub content: String,
-    pub status: String, // "pending", "in_progress", "completed"
+    pub status: String, // "pending", "in_progress", "completed", "failed"
     pub created_at: String,
     pub updated_at: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub notification_type: String, // "success", "error", "warning", "info"
+    pub title: String,
+    pub message: String,
+    pub project_id: Option<String>,
+    pub session_id: Option<String>,
+    pub message_uuid: Option<String>, // Message the notification points at, e.g. an error
+    pub read: bool,
+    pub created_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default = "default_true")]
@@ -29,18 +43,73 @@ pub struct Config {
     pub error_alerts: bool,
     #[serde(default)]
     pub sound_enabled: bool,
+    #[serde(default = "default_idle_minutes")]
+    pub session_idle_minutes: u32, // Quiet time before a session counts as idle or finished
     #[serde(default = "default_token_limit")]
     pub daily_token_limit: u32,
+    #[serde(default = "default_budget_thresholds")]
+    pub budget_thresholds: Vec<u32>, // Percentages of the daily token limit
+    #[serde(default)]
+    pub timezone: Option<String>, // IANA name, system local zone when unset
+    #[serde(default)]
+    pub approvals_enabled: bool, // Ask Tusker before tools run, through the PreToolUse hook
+    #[serde(default = "default_approval_timeout")]
+    pub approval_timeout_secs: u32,
+    #[serde(default = "default_approval_decision")]
+    pub approval_default: String, // "allow", "deny" or "ask", used when an approval times out
+    #[serde(default = "default_memory_file_token_limit")]
+    pub memory_file_token_limit: u32, // Approximate tokens a single CLAUDE.md may use
+    #[serde(default = "default_memory_total_token_limit")]
+    pub memory_total_token_limit: u32, // Approximate tokens all memory loaded at startup may use
+    #[serde(default)]
+    pub claude_cli_path: Option<String>, // `claude` from PATH when unset
+    #[serde(default = "default_task_concurrency")]
+    pub task_concurrency: u32, // Tasks the runner executes at once
+    #[serde(default = "default_task_permission_mode")]
+    pub task_permission_mode: String, // Permission mode for tasks run headless
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_idle_minutes() -> u32 {
+    2
+}
+
 fn default_token_limit() -> u32 {
     50000
 }
 
+fn default_budget_thresholds() -> Vec<u32> {
+    vec![50, 80, 100]
+}
+
+fn default_approval_timeout() -> u32 {
+    120
+}
+
+fn default_approval_decision() -> String {
+    "ask".to_string()
+}
+
+fn default_memory_file_token_limit() -> u32 {
+    5000
+}
+
+fn default_memory_total_token_limit() -> u32 {
+    10000
+}
+
+fn default_task_concurrency() -> u32 {
+    1
+}
+
+// Headless runs can't answer permission prompts, so let tasks edit files
+fn default_task_permission_mode() -> String {
+    "acceptEdits".to_string()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
@@ -48,7 +117,18 @@ impl Default for Config {
             session_notifications: true,
             error_alerts: true,
             sound_enabled: false,
+            session_idle_minutes: default_idle_minutes(),
             daily_token_limit: 50000,
+            budget_thresholds: default_budget_thresholds(),
+            timezone: None,
+            approvals_enabled: false,
+            approval_timeout_secs: default_approval_timeout(),
+            approval_default: default_approval_decision(),
+            memory_file_token_limit: default_memory_file_token_limit(),
+            memory_total_token_limit: default_memory_total_token_limit(),
+            claude_cli_path: None,
+            task_concurrency: default_task_concurrency(),
+            task_permission_mode: default_task_permission_mode(),
         }
     }
 }
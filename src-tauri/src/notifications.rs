@@ -0,0 +1,139 @@
+use rusqlite::{params, Connection};
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::models::Notification;
+
+pub const NOTIFICATION_TYPES: [&str; 4] = ["success", "error", "warning", "info"];
+
+const NOTIFICATION_COLUMNS: &str =
+    "id, type, title, message, project_id, session_id, message_uuid, read, created_at";
+
+fn row_to_notification(row: &rusqlite::Row) -> rusqlite::Result<Notification> {
+    Ok(Notification {
+        id: row.get(0)?,
+        notification_type: row.get(1)?,
+        title: row.get(2)?,
+        message: row.get(3)?,
+        project_id: row.get(4)?,
+        session_id: row.get(5)?,
+        message_uuid: row.get(6)?,
+        read: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+/// Store a new unread notification
+pub fn create(
+    conn: &Connection,
+    notification_type: &str,
+    title: &str,
+    message: &str,
+    project_id: Option<&str>,
+    session_id: Option<&str>,
+    message_uuid: Option<&str>,
+) -> Result<Notification, String> {
+    if !NOTIFICATION_TYPES.contains(&notification_type) {
+        return Err(format!(
+            "Invalid notification type. Must be one of: {}",
+            NOTIFICATION_TYPES.join(", ")
+        ));
+    }
+
+    let notification = Notification {
+        id: Uuid::new_v4().to_string(),
+        notification_type: notification_type.to_string(),
+        title: title.to_string(),
+        message: message.to_string(),
+        project_id: project_id.map(String::from),
+        session_id: session_id.map(String::from),
+        message_uuid: message_uuid.map(String::from),
+        read: false,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    conn.execute(
+        "INSERT INTO notifications (id, type, title, message, project_id, session_id, message_uuid, read, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0, ?8)",
+        params![
+            &notification.id,
+            &notification.notification_type,
+            &notification.title,
+            &notification.message,
+            &notification.project_id,
+            &notification.session_id,
+            &notification.message_uuid,
+            &notification.created_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(notification)
+}
+
+/// Tell the frontend about a stored notification
+pub fn emit(app: &AppHandle, notification: &Notification) {
+    let _ = app.emit("notification-created", notification);
+}
+
+/// Store a notification and emit `notification-created`
+///
+/// Locks the database, so callers already holding the connection should use
+/// `create` and `emit` instead.
+pub fn raise(
+    app: &AppHandle,
+    notification_type: &str,
+    title: &str,
+    message: &str,
+    project_id: Option<&str>,
+    session_id: Option<&str>,
+    message_uuid: Option<&str>,
+) -> Result<Notification, String> {
+    let notification = {
+        let db = app.state::<Database>();
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        create(
+            &conn,
+            notification_type,
+            title,
+            message,
+            project_id,
+            session_id,
+            message_uuid,
+        )?
+    };
+
+    emit(app, &notification);
+    Ok(notification)
+}
+
+/// Notifications newest first, optionally of a single type
+pub fn list(conn: &Connection, notification_type: Option<&str>) -> Result<Vec<Notification>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM notifications
+             WHERE ?1 IS NULL OR type = ?1
+             ORDER BY created_at DESC",
+            NOTIFICATION_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let notifications = stmt
+        .query_map(params![notification_type], row_to_notification)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(notifications)
+}
+
+/// Id of the tracked project at a path, for linking notifications to projects
+pub fn project_id_for_path(conn: &Connection, project_path: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT id FROM projects WHERE path = ?1",
+        params![project_path],
+        |row| row.get(0),
+    )
+    .ok()
+}
@@ -0,0 +1,539 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use uuid::Uuid;
+
+use crate::claude::task_sessions;
+use crate::commands::config::load_config;
+use crate::commands::projects::project_path;
+use crate::commands::tasks::{get_task, set_task_status};
+use crate::db::Database;
+use crate::launcher::{self, ClaudeProcess, EventSink, LaunchOptions, Launcher, ProcessEvent};
+use crate::models::Config;
+
+/// One attempt at executing a task with `claude -p`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRun {
+    pub id: String,
+    pub task_id: String,
+    pub project_id: String,
+    pub status: String, // "queued", "running", "completed", "failed" or "cancelled"
+    pub process_id: Option<String>, // Matches `claude-output` events while running
+    pub session_id: Option<String>,
+    pub result: Option<String>, // Claude's final message
+    pub error: Option<String>,
+    pub cost_usd: Option<f64>,
+    pub duration_ms: Option<i64>,
+    pub num_turns: Option<i64>,
+    pub queued_at: String,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+}
+
+/// What a run reported through its stream-json output
+#[derive(Debug, Default)]
+struct RunSummary {
+    session_id: Option<String>,
+    finished: bool, // A `result` event arrived
+    is_error: bool,
+    result: Option<String>,
+    error: Option<String>,
+    cost_usd: Option<f64>,
+    duration_ms: Option<i64>,
+    num_turns: Option<i64>,
+    last_stderr: Option<String>,
+}
+
+impl RunSummary {
+    /// Take what matters from one stream-json event
+    fn apply(&mut self, line: &str) {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            return;
+        };
+
+        if let Some(session_id) = event["session_id"].as_str() {
+            self.session_id.get_or_insert_with(|| session_id.to_string());
+        }
+
+        if event["type"] != "result" {
+            return;
+        }
+
+        let subtype = event["subtype"].as_str().unwrap_or_default();
+        self.finished = true;
+        self.is_error = event["is_error"].as_bool().unwrap_or(false) || subtype != "success";
+        self.result = event["result"].as_str().map(String::from);
+        self.cost_usd = event["total_cost_usd"].as_f64();
+        self.duration_ms = event["duration_ms"].as_i64();
+        self.num_turns = event["num_turns"].as_i64();
+
+        if self.is_error {
+            self.error = self.result.clone().or_else(|| Some(subtype.replace('_', " ")));
+        }
+    }
+
+    /// Final run status and error for a process that has exited
+    fn outcome(&self, process: &ClaudeProcess) -> (&'static str, Option<String>) {
+        if process.status == "stopped" {
+            return ("cancelled", Some("Stopped from Tusker".to_string()));
+        }
+
+        if self.finished && !self.is_error && process.exit_code == Some(0) {
+            return ("completed", None);
+        }
+
+        let error = self.error.clone().or_else(|| self.last_stderr.clone()).unwrap_or_else(|| {
+            match process.exit_code {
+                Some(code) => format!("claude exited with code {} without a result", code),
+                None => "claude was killed without a result".to_string(),
+            }
+        });
+
+        ("failed", Some(error))
+    }
+}
+
+#[derive(Default)]
+struct Queue {
+    waiting: VecDeque<String>,         // Run ids in the order they were queued
+    running: HashMap<String, String>, // Run id to launcher process id
+}
+
+/// Runs waiting for a free slot and runs in progress
+#[derive(Default)]
+pub struct TaskRunner {
+    queue: Mutex<Queue>,
+}
+
+/// Arguments for a headless run of a task
+fn run_args(task_content: &str, config: &Config) -> Result<Vec<String>, String> {
+    let options = LaunchOptions {
+        prompt: Some(task_content.to_string()),
+        permission_mode: Some(config.task_permission_mode.clone()),
+        ..Default::default()
+    };
+
+    let mut args: Vec<String> = ["-p", "--output-format", "stream-json", "--verbose"]
+        .map(String::from)
+        .to_vec();
+    args.extend(launcher::claude_args(&options)?);
+
+    Ok(args)
+}
+
+fn map_run(row: &rusqlite::Row) -> rusqlite::Result<TaskRun> {
+    Ok(TaskRun {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        project_id: row.get(2)?,
+        status: row.get(3)?,
+        process_id: row.get(4)?,
+        session_id: row.get(5)?,
+        result: row.get(6)?,
+        error: row.get(7)?,
+        cost_usd: row.get(8)?,
+        duration_ms: row.get(9)?,
+        num_turns: row.get(10)?,
+        queued_at: row.get(11)?,
+        started_at: row.get(12)?,
+        ended_at: row.get(13)?,
+    })
+}
+
+const RUN_COLUMNS: &str = "id, task_id, project_id, status, process_id, session_id, result, error,
+     cost_usd, duration_ms, num_turns, queued_at, started_at, ended_at";
+
+pub fn get_run(conn: &Connection, id: &str) -> Result<TaskRun, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM task_runs WHERE id = ?1", RUN_COLUMNS),
+        params![id],
+        map_run,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Runs of a project's tasks, or of one task, newest first
+pub fn list_runs(
+    conn: &Connection,
+    project_id: Option<&str>,
+    task_id: Option<&str>,
+) -> Result<Vec<TaskRun>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM task_runs
+             WHERE (?1 IS NULL OR project_id = ?1) AND (?2 IS NULL OR task_id = ?2)
+             ORDER BY queued_at DESC",
+            RUN_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let runs = stmt
+        .query_map(params![project_id, task_id], map_run)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(runs)
+}
+
+/// A project's pending tasks, oldest first
+pub fn pending_task_ids(conn: &Connection, project_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM tasks WHERE project_id = ?1 AND status = 'pending' ORDER BY created_at")
+        .map_err(|e| e.to_string())?;
+
+    let ids = stmt
+        .query_map(params![project_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(ids)
+}
+
+/// Give up on runs left queued or running when the app last closed
+pub fn recover(conn: &Connection) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+
+    conn.execute(
+        "UPDATE tasks SET status = 'pending', updated_at = ?1
+         WHERE status = 'in_progress'
+           AND id IN (SELECT task_id FROM task_runs WHERE status IN ('queued', 'running'))",
+        params![&now],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE task_runs SET status = 'cancelled', error = 'Tusker closed before the run finished', ended_at = ?1
+         WHERE status IN ('queued', 'running')",
+        params![&now],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn emit_run(app: &AppHandle, run_id: &str) {
+    let db = app.state::<Database>();
+    let run = db.conn.lock().ok().and_then(|conn| get_run(&conn, run_id).ok());
+
+    if let Some(run) = run {
+        let _ = app.emit("task-run-updated", &run);
+    }
+}
+
+/// Queue pending tasks to run headless, starting as many as the concurrency limit allows
+///
+/// Emits `task-run-updated` with the `TaskRun` whenever a run changes status.
+pub fn enqueue(app: &AppHandle, task_ids: &[String]) -> Result<Vec<TaskRun>, String> {
+    let runner = app.state::<TaskRunner>();
+    let mut runs = vec![];
+
+    {
+        let db = app.state::<Database>();
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let mut queue = runner.queue.lock().map_err(|e| e.to_string())?;
+
+        let tasks = task_ids
+            .iter()
+            .map(|id| get_task(&conn, id))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(task) = tasks.iter().find(|t| t.status != "pending") {
+            return Err(format!("Task '{}' is {}, only pending tasks can run", task.content, task.status));
+        }
+
+        for task in tasks {
+            let task_id = &task.id;
+            let active: bool = conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM task_runs WHERE task_id = ?1 AND status IN ('queued', 'running'))",
+                    params![task_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if active {
+                continue;
+            }
+
+            let id = Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO task_runs (id, task_id, project_id, status, queued_at)
+                 VALUES (?1, ?2, ?3, 'queued', ?4)",
+                params![&id, task_id, &task.project_id, chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| e.to_string())?;
+
+            queue.waiting.push_back(id.clone());
+            runs.push(get_run(&conn, &id)?);
+        }
+    }
+
+    for run in &runs {
+        let _ = app.emit("task-run-updated", run);
+    }
+    pump(app);
+
+    Ok(runs)
+}
+
+/// Cancel a queued run or stop a running one
+pub fn cancel(app: &AppHandle, run_id: &str) -> Result<(), String> {
+    let runner = app.state::<TaskRunner>();
+    let mut queue = runner.queue.lock().map_err(|e| e.to_string())?;
+
+    if let Some(process_id) = queue.running.get(run_id) {
+        // The exit is recorded by `finish`
+        return app.state::<Launcher>().stop(process_id);
+    }
+
+    let position = queue
+        .waiting
+        .iter()
+        .position(|id| id == run_id)
+        .ok_or("Run is not queued or running")?;
+    queue.waiting.remove(position);
+    drop(queue);
+
+    {
+        let db = app.state::<Database>();
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE task_runs SET status = 'cancelled', ended_at = ?1 WHERE id = ?2",
+            params![chrono::Utc::now().to_rfc3339(), run_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    emit_run(app, run_id);
+    Ok(())
+}
+
+/// Start queued runs while there are free slots
+fn pump(app: &AppHandle) {
+    let limit = load_config().map(|c| c.task_concurrency).unwrap_or(1).max(1) as usize;
+    let runner = app.state::<TaskRunner>();
+
+    loop {
+        let run_id = {
+            let Ok(mut queue) = runner.queue.lock() else {
+                return;
+            };
+            if queue.running.len() >= limit {
+                return;
+            }
+            let Some(run_id) = queue.waiting.pop_front() else {
+                return;
+            };
+            // Hold the slot until the process id is known
+            queue.running.insert(run_id.clone(), String::new());
+            run_id
+        };
+
+        if let Err(e) = start(app, &run_id) {
+            record_failure(app, &run_id, &e);
+            if let Ok(mut queue) = runner.queue.lock() {
+                queue.running.remove(&run_id);
+            }
+        }
+    }
+}
+
+/// Move a run's task to in progress and start `claude -p` for it
+fn start(app: &AppHandle, run_id: &str) -> Result<(), String> {
+    let config = load_config()?;
+    let db = app.state::<Database>();
+
+    let task = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let run = get_run(&conn, run_id)?;
+        let task = set_task_status(&conn, &run.task_id, "in_progress")?;
+
+        conn.execute(
+            "UPDATE task_runs SET status = 'running', started_at = ?1 WHERE id = ?2",
+            params![chrono::Utc::now().to_rfc3339(), run_id],
+        )
+        .map_err(|e| e.to_string())?;
+        task
+    };
+    emit_run(app, run_id);
+
+    let args = run_args(&task.content, &config)?;
+    let project_path = project_path(&db, &task.project_id)?;
+
+    let summary = Arc::new(Mutex::new(RunSummary::default()));
+    let sink: EventSink = {
+        let app = app.clone();
+        let run_id = run_id.to_string();
+
+        Arc::new(move |event| match event {
+            ProcessEvent::Output(output) => {
+                if let Ok(mut summary) = summary.lock() {
+                    if output.stream == "stdout" {
+                        summary.apply(&output.line);
+                    } else if !output.line.trim().is_empty() {
+                        summary.last_stderr = Some(output.line.clone());
+                    }
+                }
+                let _ = app.emit("claude-output", &output);
+            }
+            ProcessEvent::Exited(process) => {
+                if let Ok(summary) = summary.lock() {
+                    finish(&app, &run_id, &process, &summary);
+                }
+                let _ = app.emit("claude-exited", &process);
+            }
+        })
+    };
+
+    let launcher = app.state::<Launcher>();
+    let process = launcher.spawn(&launcher::cli_path(&config), args, &task.project_id, &project_path, sink)?;
+
+    let runner = app.state::<TaskRunner>();
+    if let Some(slot) = runner.queue.lock().map_err(|e| e.to_string())?.running.get_mut(run_id) {
+        *slot = process.id.clone();
+    }
+
+    let updated = {
+        let conn = db.conn.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE task_runs SET process_id = ?1 WHERE id = ?2 AND status = 'running'",
+            params![&process.id, run_id],
+        )
+        .map_err(|e| e.to_string())?
+    };
+    if updated > 0 {
+        emit_run(app, run_id);
+    }
+
+    Ok(())
+}
+
+/// Record how a run ended, update its task and start the next queued run
+fn finish(app: &AppHandle, run_id: &str, process: &ClaudeProcess, summary: &RunSummary) {
+    let (status, error) = summary.outcome(process);
+    let task_status = match status {
+        "completed" => "completed",
+        "failed" => "failed",
+        _ => "pending",
+    };
+
+    // Runs killed before reporting still took wall clock time
+    let duration_ms = summary.duration_ms.or_else(|| {
+        let started = chrono::DateTime::parse_from_rfc3339(&process.started_at).ok()?;
+        let ended = chrono::DateTime::parse_from_rfc3339(process.ended_at.as_deref()?).ok()?;
+        Some((ended - started).num_milliseconds())
+    });
+
+    let db = app.state::<Database>();
+    if let Ok(conn) = db.conn.lock() {
+        let result = conn.execute(
+            "UPDATE task_runs SET status = ?1, session_id = ?2, result = ?3, error = ?4, cost_usd = ?5,
+                 duration_ms = ?6, num_turns = ?7, ended_at = ?8
+             WHERE id = ?9",
+            params![
+                status,
+                &summary.session_id,
+                &summary.result,
+                &error,
+                summary.cost_usd,
+                duration_ms,
+                summary.num_turns,
+                &process.ended_at,
+                run_id
+            ],
+        );
+        let task = get_run(&conn, run_id).and_then(|run| {
+            if let Some(session_id) = &summary.session_id {
+                task_sessions::attach(&conn, &run.task_id, session_id, "run")?;
+            }
+            set_task_status(&conn, &run.task_id, task_status)
+        });
+        if let Err(e) = result.map_err(|e| e.to_string()).and(task) {
+            eprintln!("Failed to record task run {}: {}", run_id, e);
+        }
+    }
+
+    emit_run(app, run_id);
+
+    if let Ok(mut queue) = app.state::<TaskRunner>().queue.lock() {
+        queue.running.remove(run_id);
+    }
+    pump(app);
+}
+
+/// Mark a run that could not start as failed, along with its task
+fn record_failure(app: &AppHandle, run_id: &str, error: &str) {
+    let db = app.state::<Database>();
+    if let Ok(conn) = db.conn.lock() {
+        let _ = conn.execute(
+            "UPDATE task_runs SET status = 'failed', error = ?1, ended_at = ?2 WHERE id = ?3",
+            params![error, chrono::Utc::now().to_rfc3339(), run_id],
+        );
+        if let Ok(run) = get_run(&conn, run_id) {
+            let _ = set_task_status(&conn, &run.task_id, "failed");
+        }
+    }
+
+    emit_run(app, run_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(status: &str, exit_code: Option<i32>) -> ClaudeProcess {
+        ClaudeProcess {
+            id: "p".to_string(),
+            project_id: "project".to_string(),
+            cwd: "/work".to_string(),
+            pid: 1,
+            args: vec![],
+            status: status.to_string(),
+            exit_code,
+            started_at: "2026-01-01T00:00:00Z".to_string(),
+            ended_at: None,
+        }
+    }
+
+    #[test]
+    fn test_run_summary() {
+        let mut summary = RunSummary::default();
+        for line in [
+            r#"{"type":"system","subtype":"init","session_id":"s1","tools":[]}"#,
+            "not json",
+            r#"{"type":"assistant","session_id":"s1","message":{"content":[]}}"#,
+            r#"{"type":"result","subtype":"success","is_error":false,"result":"Done","session_id":"s1",
+               "total_cost_usd":0.042,"duration_ms":5300,"num_turns":4}"#,
+        ] {
+            summary.apply(&line.replace('\n', ""));
+        }
+
+        assert_eq!(summary.session_id.as_deref(), Some("s1"));
+        assert_eq!(summary.cost_usd, Some(0.042));
+        assert_eq!(summary.duration_ms, Some(5300));
+        assert_eq!(summary.outcome(&process("exited", Some(0))), ("completed", None));
+        assert_eq!(summary.outcome(&process("stopped", None)).0, "cancelled");
+    }
+
+    #[test]
+    fn test_run_failures() {
+        let mut summary = RunSummary::default();
+        summary.apply(r#"{"type":"result","subtype":"error_max_turns","is_error":true,"session_id":"s2"}"#);
+        assert_eq!(
+            summary.outcome(&process("exited", Some(1))),
+            ("failed", Some("error max turns".to_string()))
+        );
+
+        let crashed = RunSummary {
+            last_stderr: Some("Invalid API key".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            crashed.outcome(&process("exited", Some(1))),
+            ("failed", Some("Invalid API key".to_string()))
+        );
+    }
+}
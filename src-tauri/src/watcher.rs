@@ -0,0 +1,343 @@
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::budget::{self, BudgetAlert};
+use crate::claude::errors::{self, ErrorKind, SessionError};
+use crate::claude::index::{self, FileUpdate};
+use crate::claude::paths::{encode_project_path, get_claude_projects_dir};
+use crate::claude::sessions::truncate_string;
+use crate::claude::ClaudeSession;
+use crate::commands::config::{load_config, load_day_zone};
+use crate::db::Database;
+use crate::lifecycle::{SessionLifecycleEvent, SessionState, SessionTracker};
+use crate::notifications;
+
+/// Quiet period to wait for before processing a burst of file events
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Longest a file event waits to be processed while writes keep coming
+const MAX_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// How often to check whether Claude has created its projects directory
+const MISSING_DIR_RETRY: Duration = Duration::from_secs(10);
+
+/// How often live sessions are checked for having gone quiet
+const LIFECYCLE_TICK: Duration = Duration::from_secs(15);
+
+/// Watch the Claude projects directory and emit session events as JSONL files change
+///
+/// Waits in the background for the directory if Claude hasn't created it yet.
+/// Emits `session-started` and `session-updated` with a `ClaudeSession` payload,
+/// `session-message` with each appended `ClaudeMessage` and `budget-threshold`
+/// with a `BudgetAlert` when new usage crosses a daily budget threshold, which is
+/// also raised as a notification. Sessions written to while watching emit
+/// `session-lifecycle` when they start, go idle or finish their turn, raising
+/// notifications when `Config.session_notifications` is on. A session starts,
+/// with `session-started`, on the first write the watcher sees, even when a
+/// command indexed it first. Errors in messages
+/// written since the watcher started are raised as notifications when
+/// `Config.error_alerts` is on, including those indexed by other refreshes.
+pub fn start(app: AppHandle) -> Result<(), String> {
+    let projects_dir = get_claude_projects_dir()
+        .ok_or_else(|| "Could not find Claude projects directory".to_string())?;
+
+    std::thread::spawn(move || {
+        // Claude creates the directory with its first session, e.g. right after install
+        while !projects_dir.exists() {
+            std::thread::sleep(MISSING_DIR_RETRY);
+        }
+
+        if let Err(e) = watch(&app, &projects_dir) {
+            eprintln!("Session watcher disabled: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+/// Process file events until the watcher stops
+fn watch(app: &AppHandle, projects_dir: &Path) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+
+    // The watcher stops when dropped, so it lives as long as this loop
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| format!("Failed to create watcher: {}", e))?;
+    watcher
+        .watch(projects_dir, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch Claude projects directory: {}", e))?;
+
+    let mut pending: HashSet<PathBuf> = HashSet::new();
+    let mut pending_since: Option<Instant> = None;
+    let mut tracker = SessionTracker::default();
+    let mut last_tick = Instant::now();
+    let started_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+
+    loop {
+        let timeout = match pending_since {
+            Some(since) => DEBOUNCE.min(MAX_DEBOUNCE.saturating_sub(since.elapsed())),
+            None => LIFECYCLE_TICK,
+        };
+
+        let quiet = match rx.recv_timeout(timeout) {
+            Ok(Ok(event)) => {
+                pending.extend(
+                    event
+                        .paths
+                        .into_iter()
+                        .filter(|p| is_session_file(projects_dir, p)),
+                );
+                if !pending.is_empty() {
+                    pending_since.get_or_insert_with(Instant::now);
+                }
+                false
+            }
+            Ok(Err(_)) => false,
+            Err(RecvTimeoutError::Timeout) => true,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+
+        // Flush after a quiet period, or while Claude keeps writing, once the first change is old enough
+        if pending_since.is_some_and(|since| quiet || since.elapsed() >= MAX_DEBOUNCE) {
+            let paths: Vec<PathBuf> = pending.drain().collect();
+            pending_since = None;
+            process_changes(app, projects_dir, &paths, &mut tracker);
+            check_errors(app, &started_at);
+        }
+
+        if last_tick.elapsed() >= LIFECYCLE_TICK {
+            check_lifecycle(app, &mut tracker);
+            check_errors(app, &started_at);
+            last_tick = Instant::now();
+        }
+    }
+
+    Ok(())
+}
+
+/// Session files live directly inside a project directory
+fn is_session_file(projects_dir: &Path, path: &Path) -> bool {
+    path.extension().map(|e| e == "jsonl").unwrap_or(false)
+        && path.parent().and_then(|p| p.parent()) == Some(projects_dir)
+}
+
+/// Index changed files for tracked projects and emit their updates
+fn process_changes(
+    app: &AppHandle,
+    projects_dir: &Path,
+    paths: &[PathBuf],
+    tracker: &mut SessionTracker,
+) {
+    let db = app.state::<Database>();
+
+    let (updates, alerts): (Vec<FileUpdate>, Vec<BudgetAlert>) = {
+        let mut conn = match db.conn.lock() {
+            Ok(conn) => conn,
+            Err(_) => return,
+        };
+
+        let tracked_paths: Vec<String> = match conn.prepare("SELECT path FROM projects") {
+            Ok(mut stmt) => stmt
+                .query_map([], |row| row.get(0))
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+                .unwrap_or_default(),
+            Err(_) => return,
+        };
+
+        let updates: Vec<FileUpdate> = paths
+            .iter()
+            .filter_map(|path| {
+                let dir_name = path.parent()?.strip_prefix(projects_dir).ok()?.to_str()?;
+                let project_path = tracked_paths
+                    .iter()
+                    .find(|p| encode_project_path(p) == dir_name)?;
+                index::refresh_file(&mut conn, project_path, path).ok()
+            })
+            .collect();
+
+        let alerts = if updates.iter().any(|u| !u.messages.is_empty()) {
+            check_budget(&conn)
+        } else {
+            vec![]
+        };
+
+        (updates, alerts)
+    };
+
+    let now = Instant::now();
+
+    for update in updates {
+        for session in &update.updated {
+            if let Some(started) = tracker.updated(session, now) {
+                let _ = app.emit("session-started", session);
+                notify_lifecycle(app, started);
+            }
+        }
+        for message in &update.messages {
+            let _ = app.emit("session-message", message);
+            tracker.message(message);
+        }
+        for session in &update.updated {
+            let _ = app.emit("session-updated", session);
+        }
+    }
+
+    for alert in &alerts {
+        let _ = app.emit("budget-threshold", alert);
+        notify_budget_alert(app, alert);
+    }
+}
+
+/// Notify errors indexed since the last check, by the watcher or by a command
+///
+/// Errors in messages written before the watcher started are dropped.
+fn check_errors(app: &AppHandle, started_at: &str) {
+    let errors: Vec<(SessionError, ClaudeSession)> = {
+        let db = app.state::<Database>();
+        let conn = match db.conn.lock() {
+            Ok(conn) => conn,
+            Err(_) => return,
+        };
+
+        errors::take_unnotified(&conn, started_at)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|error| {
+                let session = index::get_session(&conn, &error.session_id).ok()??;
+                Some((error, session))
+            })
+            .collect()
+    };
+
+    for (error, session) in &errors {
+        notify_error(app, session, error);
+    }
+}
+
+/// Report sessions that have gone quiet since their last write
+fn check_lifecycle(app: &AppHandle, tracker: &mut SessionTracker) {
+    let idle_minutes = load_config().map(|c| c.session_idle_minutes).unwrap_or(2);
+    let quiet_for = Duration::from_secs(u64::from(idle_minutes.max(1)) * 60);
+
+    for event in tracker.tick(Instant::now(), quiet_for) {
+        notify_lifecycle(app, event);
+    }
+}
+
+/// Emit a lifecycle event and raise its notification if session notifications are on
+fn notify_lifecycle(app: &AppHandle, mut event: SessionLifecycleEvent) {
+    let config = load_config().unwrap_or_default();
+    event.sound = config.session_notifications && config.sound_enabled;
+    let _ = app.emit("session-lifecycle", &event);
+
+    if !config.session_notifications {
+        return;
+    }
+
+    let session = &event.session;
+    let (notification_type, title, detail) = match event.state {
+        SessionState::Started => (
+            "info",
+            "Session started",
+            if session.first_message.is_empty() {
+                "New session started".to_string()
+            } else {
+                truncate_string(&session.first_message, 80)
+            },
+        ),
+        SessionState::Finished => (
+            "success",
+            "Claude is waiting for you",
+            "Finished its turn".to_string(),
+        ),
+        SessionState::Idle => (
+            "warning",
+            "Session idle",
+            format!("No activity for {} minutes", config.session_idle_minutes),
+        ),
+        SessionState::Active => return,
+    };
+
+    let project_id = {
+        let db = app.state::<Database>();
+        let conn = match db.conn.lock() {
+            Ok(conn) => conn,
+            Err(_) => return,
+        };
+        notifications::project_id_for_path(&conn, &session.project_path)
+    };
+
+    let _ = notifications::raise(
+        app,
+        notification_type,
+        title,
+        &format!("{}: {}", session.project_name, detail),
+        project_id.as_deref(),
+        Some(&session.id),
+        None,
+    );
+}
+
+/// Raise a notification for a session error if error alerts are on
+fn notify_error(app: &AppHandle, session: &ClaudeSession, error: &SessionError) {
+    if !load_config().map(|c| c.error_alerts).unwrap_or(true) {
+        return;
+    }
+
+    let title = match error.kind {
+        ErrorKind::ApiError => "API error",
+        ErrorKind::ToolError => "Tool call failed",
+        ErrorKind::BashExit => "Command failed",
+        ErrorKind::RepeatedFailure => "Repeated failing tool call",
+    };
+
+    let project_id = {
+        let db = app.state::<Database>();
+        let conn = match db.conn.lock() {
+            Ok(conn) => conn,
+            Err(_) => return,
+        };
+        notifications::project_id_for_path(&conn, &session.project_path)
+    };
+
+    let _ = notifications::raise(
+        app,
+        "error",
+        title,
+        &format!("{}: {}", session.project_name, error.detail),
+        project_id.as_deref(),
+        Some(&session.id),
+        Some(&error.message_uuid),
+    );
+}
+
+fn notify_budget_alert(app: &AppHandle, alert: &BudgetAlert) {
+    let status = &alert.status;
+    let notification_type = if alert.threshold >= 100 { "error" } else { "warning" };
+
+    let _ = notifications::raise(
+        app,
+        notification_type,
+        &format!("Daily token usage at {}% of limit", alert.threshold),
+        &format!(
+            "{} of {} tokens used today, {} projected by end of day",
+            status.tokens_used, status.daily_token_limit, status.projected_tokens
+        ),
+        None,
+        None,
+        None,
+    );
+}
+
+/// Thresholds newly crossed by today's usage, none if the config can't be read
+fn check_budget(conn: &rusqlite::Connection) -> Vec<BudgetAlert> {
+    let (config, zone) = match (load_config(), load_day_zone()) {
+        (Ok(config), Ok(zone)) => (config, zone),
+        _ => return vec![],
+    };
+
+    budget::check_thresholds(conn, &config, zone).unwrap_or_default()
+}
@@ -0,0 +1,84 @@
+//! Forwards a Claude Code hook event to a running Tusker
+//!
+//! Claude Code passes the event as JSON on stdin. Tusker's reply, if any, is
+//! printed to stdout for Claude Code to read. When Tusker is not running the
+//! event is dropped, so the hook never gets in Claude's way.
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(600);
+
+fn main() {
+    let mut payload = String::new();
+    if io::stdin().read_to_string(&mut payload).is_err() {
+        return;
+    }
+
+    match forward(&payload) {
+        Ok(reply) if !reply.is_empty() && reply != "{}" => println!("{}", reply),
+        Ok(_) => {}
+        Err(e) => eprintln!("tusker-hook: {}", e),
+    }
+}
+
+/// Port and token of the running Tusker, from `~/.tusker/hook.json`
+fn read_endpoint() -> Result<(u16, String), String> {
+    let path = dirs::home_dir()
+        .ok_or("Could not find home directory")?
+        .join(".tusker")
+        .join("hook.json");
+
+    let content = fs::read_to_string(&path).map_err(|_| "Tusker is not running".to_string())?;
+    let endpoint: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    let port = endpoint["port"].as_u64().ok_or("Invalid hook.json")? as u16;
+    let token = endpoint["token"].as_str().ok_or("Invalid hook.json")?.to_string();
+
+    Ok((port, token))
+}
+
+/// POST the payload to Tusker and return the response body
+fn forward(payload: &str) -> Result<String, String> {
+    let (port, token) = read_endpoint()?;
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+        .map_err(|_| "Tusker is not running".to_string())?;
+    stream
+        .set_read_timeout(Some(RESPONSE_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+
+    write!(
+        stream,
+        "POST /hook HTTP/1.1\r\nHost: 127.0.0.1\r\nAuthorization: Bearer {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        token,
+        payload.len(),
+        payload
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut reader = BufReader::new(stream);
+    let mut status_line = String::new();
+    reader.read_line(&mut status_line).map_err(|e| e.to_string())?;
+
+    // Skip headers, the body runs until the connection closes
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(|e| e.to_string())? == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut body = String::new();
+    reader.read_to_string(&mut body).map_err(|e| e.to_string())?;
+
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("{}: {}", status_line.trim(), body));
+    }
+
+    Ok(body)
+}
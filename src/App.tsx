@@ -7,7 +7,23 @@ import { SessionsPage } from "@/pages/SessionsPage";
 import { NotificationsPage } from "@/pages/NotificationsPage";
 import { SettingsPage } from "@/pages/SettingsPage";
 import { CommandPalette } from "@/components/CommandPalette";
+import { ApprovalPrompt } from "@/components/ApprovalPrompt";
 import { useState, useEffect } from "react";
+import { onSessionLifecycle } from "@/services/tauri";
+
+// Short chime for session notifications
+function playChime() {
+  const ctx = new AudioContext();
+  const osc = ctx.createOscillator();
+  const gain = ctx.createGain();
+  osc.frequency.value = 880;
+  gain.gain.setValueAtTime(0.1, ctx.currentTime);
+  gain.gain.exponentialRampToValueAtTime(0.001, ctx.currentTime + 0.4);
+  osc.connect(gain).connect(ctx.destination);
+  osc.start();
+  osc.stop(ctx.currentTime + 0.4);
+  osc.onended = () => ctx.close();
+}
 
 function App() {
   const [commandPaletteOpen, setCommandPaletteOpen] = useState(false);
@@ -28,6 +44,17 @@ function App() {
     return () => window.removeEventListener("keydown", handleKeyDown);
   }, []);
 
+  // Play a sound when a session starts, goes idle or finishes, if enabled
+  useEffect(() => {
+    const unlisten = onSessionLifecycle((event) => {
+      if (event.sound) playChime();
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   return (
     <BrowserRouter>
       <Routes>
@@ -46,6 +73,9 @@ function App() {
         open={commandPaletteOpen}
         onClose={() => setCommandPaletteOpen(false)}
       />
+
+      {/* Tool calls waiting for approval */}
+      <ApprovalPrompt />
     </BrowserRouter>
   );
 }
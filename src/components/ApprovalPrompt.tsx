@@ -0,0 +1,108 @@
+import { useState, useEffect } from "react";
+import { ShieldAlert } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import {
+  getPendingApprovals,
+  resolveApproval,
+  onApprovalRequested,
+  onApprovalResolved,
+  type ApprovalChoice,
+  type PendingApproval,
+} from "@/services/tauri";
+
+// The part of a tool input worth showing, e.g. a Bash command or an edited file
+function describeInput(input: unknown): string {
+  if (input && typeof input === "object") {
+    const fields = input as Record<string, unknown>;
+    for (const key of ["command", "file_path", "url", "pattern"]) {
+      if (typeof fields[key] === "string") return fields[key] as string;
+    }
+  }
+  return JSON.stringify(input);
+}
+
+function secondsLeft(expiresAt: string, now: number): number {
+  return Math.max(0, Math.ceil((new Date(expiresAt).getTime() - now) / 1000));
+}
+
+// Tool calls Claude is holding until they are allowed or denied here
+export function ApprovalPrompt() {
+  const [pending, setPending] = useState<PendingApproval[]>([]);
+  const [now, setNow] = useState(Date.now());
+
+  useEffect(() => {
+    getPendingApprovals()
+      .then(setPending)
+      .catch((err) => console.error("Failed to load pending approvals:", err));
+
+    const requested = onApprovalRequested((approval) => {
+      setPending((prev) => [...prev, approval]);
+    });
+    const resolved = onApprovalResolved((decision) => {
+      setPending((prev) => prev.filter((a) => a.id !== decision.id));
+    });
+
+    return () => {
+      requested.then((fn) => fn());
+      resolved.then((fn) => fn());
+    };
+  }, []);
+
+  // Tick the countdowns while anything is waiting
+  useEffect(() => {
+    if (pending.length === 0) return;
+    const timer = setInterval(() => setNow(Date.now()), 1000);
+    return () => clearInterval(timer);
+  }, [pending.length]);
+
+  const handleResolve = async (id: string, decision: ApprovalChoice) => {
+    try {
+      await resolveApproval(id, decision);
+    } catch (err) {
+      console.error("Failed to resolve approval:", err);
+    }
+    setPending((prev) => prev.filter((a) => a.id !== id));
+  };
+
+  if (pending.length === 0) return null;
+
+  return (
+    <div className="fixed bottom-8 right-4 z-50 w-96 space-y-2">
+      {pending.map((approval) => (
+        <div
+          key={approval.id}
+          className="rounded-lg border border-warning/40 bg-bg-surface p-3 shadow-lg"
+        >
+          <div className="flex items-center justify-between gap-2">
+            <div className="flex items-center gap-1.5 text-xs font-medium text-text-primary">
+              <ShieldAlert size={14} className="text-warning" />
+              {approval.tool_name}
+              {approval.cwd && (
+                <span className="text-text-muted font-normal">
+                  in {approval.cwd.split("/").pop()}
+                </span>
+              )}
+            </div>
+            <span className="text-[10px] text-text-muted">
+              {secondsLeft(approval.expires_at, now)}s
+            </span>
+          </div>
+          <pre className="mt-2 max-h-24 overflow-auto whitespace-pre-wrap break-all rounded bg-bg-elevated px-2 py-1 text-[10px] text-text-secondary">
+            {describeInput(approval.tool_input)}
+          </pre>
+          <div className="mt-2 flex justify-end gap-1.5">
+            <Button size="sm" variant="ghost" onClick={() => handleResolve(approval.id, "ask")}>
+              Ask in Claude
+            </Button>
+            <Button size="sm" variant="outline" onClick={() => handleResolve(approval.id, "deny")}>
+              Deny
+            </Button>
+            <Button size="sm" onClick={() => handleResolve(approval.id, "allow")}>
+              Allow
+            </Button>
+          </div>
+        </div>
+      ))}
+    </div>
+  );
+}
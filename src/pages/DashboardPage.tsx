@@ -21,6 +21,7 @@ import {
   updateTaskStatus,
   getRecentSessions,
   getClaudeStats,
+  onSessionUpdated,
   type Project,
   type Task,
   type ClaudeSession,
@@ -66,6 +67,18 @@ export function DashboardPage() {
     fetchData();
   }, []);
 
+  // Keep recent sessions live as Claude writes to session files
+  useEffect(() => {
+    const unlisten = onSessionUpdated((updated) => {
+      setSessions((prev) =>
+        [updated, ...prev.filter((s) => s.id !== updated.id)].slice(0, 10)
+      );
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const fetchData = async () => {
     try {
       setLoading(true);
@@ -1,59 +1,32 @@
-import { useState } from "react";
+import { useState, useEffect } from "react";
 import { Card, CardContent } from "@/components/ui/card";
 import { Badge } from "@/components/ui/badge";
 import { CheckCircle, AlertCircle, Info, X, Bell, Check } from "lucide-react";
+import {
+  getNotifications,
+  markNotificationRead,
+  markAllNotificationsRead,
+  deleteNotification,
+  clearNotifications,
+  onNotificationCreated,
+  type Notification,
+} from "@/services/tauri";
 
-interface Notification {
-  id: number;
-  type: "success" | "error" | "warning" | "info";
-  title: string;
-  message: string;
-  time: string;
-  read: boolean;
-}
+// Format relative time
+function formatRelativeTime(timestamp: string): string {
+  const date = new Date(timestamp);
+  const now = new Date();
+  const diffMs = now.getTime() - date.getTime();
+  const diffMins = Math.floor(diffMs / 60000);
+  const diffHours = Math.floor(diffMs / 3600000);
+  const diffDays = Math.floor(diffMs / 86400000);
 
-const initialNotifications: Notification[] = [
-  {
-    id: 1,
-    type: "success",
-    title: "Session completed",
-    message: "stoxly-core: Finished implementing auth middleware",
-    time: "2m ago",
-    read: false,
-  },
-  {
-    id: 2,
-    type: "error",
-    title: "Build failed",
-    message: "crewzync: npm run build exited with code 1",
-    time: "15m ago",
-    read: false,
-  },
-  {
-    id: 3,
-    type: "info",
-    title: "Session started",
-    message: "wavezync: New session started",
-    time: "30m ago",
-    read: true,
-  },
-  {
-    id: 4,
-    type: "warning",
-    title: "High token usage",
-    message: "Daily token usage at 80% of limit",
-    time: "1h ago",
-    read: true,
-  },
-  {
-    id: 5,
-    type: "success",
-    title: "Tasks completed",
-    message: "stoxly-core: All 5 tasks completed",
-    time: "2h ago",
-    read: true,
-  },
-];
+  if (diffMins < 1) return "Just now";
+  if (diffMins < 60) return `${diffMins}m ago`;
+  if (diffHours < 24) return `${diffHours}h ago`;
+  if (diffDays < 7) return `${diffDays}d ago`;
+  return date.toLocaleDateString();
+}
 
 const typeIcons: Record<string, React.ReactNode> = {
   success: <CheckCircle size={14} className="text-success" />,
@@ -65,31 +38,65 @@ const typeIcons: Record<string, React.ReactNode> = {
 type FilterType = "all" | "success" | "error" | "warning" | "info";
 
 export function NotificationsPage() {
-  const [notifications, setNotifications] = useState<Notification[]>(initialNotifications);
+  const [notifications, setNotifications] = useState<Notification[]>([]);
   const [filter, setFilter] = useState<FilterType>("all");
 
+  useEffect(() => {
+    getNotifications()
+      .then(setNotifications)
+      .catch((err) => console.error("Failed to load notifications:", err));
+
+    const unlisten = onNotificationCreated((notification) => {
+      setNotifications((prev) => [notification, ...prev]);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const unreadCount = notifications.filter((n) => !n.read).length;
 
   const filteredNotifications = notifications.filter((n) =>
     filter === "all" ? true : n.type === filter
   );
 
-  const handleMarkRead = (id: number) => {
-    setNotifications((prev) =>
-      prev.map((n) => (n.id === id ? { ...n, read: true } : n))
-    );
+  const handleMarkRead = async (id: string) => {
+    try {
+      await markNotificationRead(id);
+      setNotifications((prev) =>
+        prev.map((n) => (n.id === id ? { ...n, read: true } : n))
+      );
+    } catch (err) {
+      console.error("Failed to mark notification read:", err);
+    }
   };
 
-  const handleDismiss = (id: number) => {
-    setNotifications((prev) => prev.filter((n) => n.id !== id));
+  const handleDismiss = async (id: string) => {
+    try {
+      await deleteNotification(id);
+      setNotifications((prev) => prev.filter((n) => n.id !== id));
+    } catch (err) {
+      console.error("Failed to delete notification:", err);
+    }
   };
 
-  const handleMarkAllRead = () => {
-    setNotifications((prev) => prev.map((n) => ({ ...n, read: true })));
+  const handleMarkAllRead = async () => {
+    try {
+      await markAllNotificationsRead();
+      setNotifications((prev) => prev.map((n) => ({ ...n, read: true })));
+    } catch (err) {
+      console.error("Failed to mark notifications read:", err);
+    }
   };
 
-  const handleClearAll = () => {
-    setNotifications([]);
+  const handleClearAll = async () => {
+    try {
+      await clearNotifications();
+      setNotifications([]);
+    } catch (err) {
+      console.error("Failed to clear notifications:", err);
+    }
   };
 
   const filters: { id: FilterType; label: string }[] = [
@@ -184,7 +191,7 @@ export function NotificationsPage() {
                         )}
                       </div>
                       <p className="text-[10px] text-text-muted">{notification.message}</p>
-                      <span className="text-[10px] text-text-muted/50">{notification.time}</span>
+                      <span className="text-[10px] text-text-muted/50">{formatRelativeTime(notification.created_at)}</span>
                     </div>
                   </div>
                   <button
@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useState, useEffect } from "react";
 import { useParams, Link } from "react-router-dom";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Badge } from "@/components/ui/badge";
@@ -30,6 +30,20 @@ import {
   X,
   Loader2,
 } from "lucide-react";
+import {
+  getPermissionRules,
+  addPermissionRule,
+  removePermissionRule,
+  suggestPermissionRules,
+  getMemoryFiles,
+  readMemoryFile,
+  saveMemoryFile,
+  type MemoryFile,
+  type PermissionRule,
+  type PermissionSuggestion,
+  type PermissionBehavior,
+  type SettingsScope,
+} from "@/services/tauri";
 
 // Mock project data
 const projectData = {
@@ -43,14 +57,6 @@ const projectData = {
   status: "active",
 };
 
-// Mock KB files
-const kbFiles = [
-  { id: 1, name: "CLAUDE.md", type: "claude", updated: "2h ago", size: "2.4 KB" },
-  { id: 2, name: "api-spec.md", type: "spec", updated: "1d ago", size: "8.1 KB" },
-  { id: 3, name: "auth-flow.md", type: "story", updated: "3d ago", size: "1.2 KB" },
-  { id: 4, name: "architecture.md", type: "kb", updated: "1w ago", size: "4.5 KB" },
-];
-
 // Mock resources (uploaded files, links)
 const resources = [
   { id: 1, name: "API Reference.pdf", type: "pdf", size: "2.1 MB", added: "1h ago" },
@@ -66,13 +72,11 @@ const mockAiMessages = [
   { id: 3, role: "user", content: "Yes, start with the architecture guidelines" },
 ];
 
-// Mock rules/permissions
-const projectRules = [
-  { id: 1, rule: "WebFetch(domain:hexdocs.pm)", type: "allow" },
-  { id: 2, rule: "Bash(mix format:*)", type: "allow" },
-  { id: 3, rule: "Bash(mix test:*)", type: "allow" },
-  { id: 4, rule: "Bash(rm -rf:*)", type: "deny" },
-];
+const behaviorBadges: Record<PermissionBehavior, "success" | "error" | "warning"> = {
+  allow: "success",
+  deny: "error",
+  ask: "warning",
+};
 
 // Mock recent sessions
 const recentSessions = [
@@ -84,7 +88,7 @@ const recentSessions = [
 type TabType = "overview" | "knowledge-base" | "sessions" | "settings";
 
 export function ProjectDetailPage() {
-  const { projectId: _projectId } = useParams();
+  const { projectId } = useParams();
   const [activeTab, setActiveTab] = useState<TabType>("overview");
   const [editingFile, setEditingFile] = useState<string | null>(null);
 
@@ -135,21 +139,30 @@ export function ProjectDetailPage() {
 
       {/* Content */}
       <div className="flex-1 overflow-auto p-4">
-        {activeTab === "overview" && <OverviewTab />}
-        {activeTab === "knowledge-base" && (
+        {activeTab === "overview" && projectId && <OverviewTab projectId={projectId} />}
+        {activeTab === "knowledge-base" && projectId && (
           <KnowledgeBaseTab
+            projectId={projectId}
             editingFile={editingFile}
             setEditingFile={setEditingFile}
           />
         )}
         {activeTab === "sessions" && <SessionsTab />}
-        {activeTab === "settings" && <SettingsTab />}
+        {activeTab === "settings" && projectId && <SettingsTab projectId={projectId} />}
       </div>
     </div>
   );
 }
 
-function OverviewTab() {
+function OverviewTab({ projectId }: { projectId: string }) {
+  const [memoryFiles, setMemoryFiles] = useState<MemoryFile[]>([]);
+
+  useEffect(() => {
+    getMemoryFiles(projectId)
+      .then(setMemoryFiles)
+      .catch((err) => console.error("Failed to load memory files:", err));
+  }, [projectId]);
+
   return (
     <div className="space-y-4">
       {/* Stats */}
@@ -187,7 +200,7 @@ function OverviewTab() {
               <FileText size={14} />
               <span className="text-xs">KB Files</span>
             </div>
-            <span className="text-lg font-semibold">{kbFiles.length}</span>
+            <span className="text-lg font-semibold">{memoryFiles.length}</span>
           </CardContent>
         </Card>
       </div>
@@ -247,27 +260,47 @@ function OverviewTab() {
 }
 
 function KnowledgeBaseTab({
+  projectId,
   editingFile,
   setEditingFile,
 }: {
-  editingFile: string | null;
+  projectId: string;
+  editingFile: string | null; // Path of the memory file being edited
   setEditingFile: (file: string | null) => void;
 }) {
-  const [editorContent, setEditorContent] = useState(`# Project Guidelines
+  const [memoryFiles, setMemoryFiles] = useState<MemoryFile[]>([]);
+  const [isSaving, setIsSaving] = useState(false);
+
+  const loadMemoryFiles = () => {
+    getMemoryFiles(projectId)
+      .then(setMemoryFiles)
+      .catch((err) => console.error("Failed to load memory files:", err));
+  };
+
+  useEffect(loadMemoryFiles, [projectId]);
 
-## Architecture
-This project uses Phoenix/Elixir with a modular architecture.
+  useEffect(() => {
+    if (!editingFile) return;
+    readMemoryFile(projectId, editingFile)
+      .then(setEditorContent)
+      .catch((err) => console.error("Failed to read memory file:", err));
+  }, [projectId, editingFile]);
 
-## Coding Standards
-- Use pattern matching where possible
-- Keep functions small and focused
-- Write tests for all public functions
+  const handleSave = async () => {
+    if (!editingFile) return;
+    setIsSaving(true);
+    try {
+      await saveMemoryFile(projectId, editingFile, editorContent);
+      setEditingFile(null);
+      loadMemoryFiles();
+    } catch (err) {
+      console.error("Failed to save memory file:", err);
+    } finally {
+      setIsSaving(false);
+    }
+  };
 
-## Key Modules
-- \`Stoxly.Accounts\` - User management
-- \`Stoxly.Trading\` - Core trading logic
-- \`Stoxly.Analytics\` - Data analysis
-`);
+  const [editorContent, setEditorContent] = useState("");
 
   const [showAiPanel, setShowAiPanel] = useState(true);
   const [aiInput, setAiInput] = useState("");
@@ -320,7 +353,9 @@ This project uses Phoenix/Elixir with a modular architecture.
               >
                 <ArrowLeft size={16} className="text-text-muted" />
               </button>
-              <span className="text-sm font-medium">{editingFile}</span>
+              <span className="text-sm font-medium">
+                {memoryFiles.find((f) => f.path === editingFile)?.name ?? editingFile}
+              </span>
               <Badge variant="outline">Markdown</Badge>
             </div>
             <div className="flex items-center gap-2">
@@ -336,8 +371,8 @@ This project uses Phoenix/Elixir with a modular architecture.
               <Button variant="ghost" size="sm" onClick={() => setEditingFile(null)}>
                 Cancel
               </Button>
-              <Button size="sm" className="gap-1.5">
-                <Save size={14} />
+              <Button size="sm" className="gap-1.5" onClick={handleSave} disabled={isSaving}>
+                {isSaving ? <Loader2 size={14} className="animate-spin" /> : <Save size={14} />}
                 Save
               </Button>
             </div>
@@ -409,7 +444,7 @@ This project uses Phoenix/Elixir with a modular architecture.
             <div className="mt-3 p-2 bg-bg-elevated rounded text-[10px] text-text-muted">
               <div className="flex items-center justify-between">
                 <span>Project context loaded</span>
-                <span className="text-accent">{kbFiles.length + resources.length} files</span>
+                <span className="text-accent">{memoryFiles.length + resources.length} files</span>
               </div>
             </div>
           </div>
@@ -451,18 +486,20 @@ This project uses Phoenix/Elixir with a modular architecture.
           <>
             {/* KB Files List */}
             <div className="flex-1 overflow-auto space-y-1">
-              {kbFiles.map((file) => (
+              {memoryFiles.map((file) => (
                 <div
-                  key={file.id}
-                  onClick={() => setEditingFile(file.name)}
+                  key={file.path}
+                  onClick={() => setEditingFile(file.path)}
                   className="flex items-center gap-2 p-2 hover:bg-bg-hover rounded cursor-pointer transition-colors group"
                 >
-                  <FileIcon type={file.type} small />
+                  <FileIcon type={file.scope === "import" ? "kb" : "claude"} small />
                   <div className="flex-1 min-w-0">
                     <div className="text-xs font-medium text-text-primary truncate">
                       {file.name}
                     </div>
-                    <div className="text-[10px] text-text-muted">{file.updated}</div>
+                    <div className="text-[10px] text-text-muted">
+                      {file.exists ? `${file.scope} · ${(file.size / 1024).toFixed(1)} KB` : `${file.scope} · not created`}
+                    </div>
                   </div>
                   <Edit3
                     size={12}
@@ -594,7 +631,10 @@ This project uses Phoenix/Elixir with a modular architecture.
               variant="outline"
               size="sm"
               className="gap-1.5"
-              onClick={() => setEditingFile("CLAUDE.md")}
+              onClick={() => {
+                const projectMemory = memoryFiles.find((f) => f.scope === "project");
+                if (projectMemory) setEditingFile(projectMemory.path);
+              }}
             >
               <FileText size={14} />
               CLAUDE.md
@@ -635,7 +675,7 @@ This project uses Phoenix/Elixir with a modular architecture.
               {projectData.name}
             </div>
             <div className="text-[10px] text-text-muted mt-1">
-              {kbFiles.length} KB files · {resources.length} resources
+              {memoryFiles.length} KB files · {resources.length} resources
             </div>
           </div>
 
@@ -814,7 +854,53 @@ function SessionsTab() {
   );
 }
 
-function SettingsTab() {
+function SettingsTab({ projectId }: { projectId: string }) {
+  const [rules, setRules] = useState<PermissionRule[]>([]);
+  const [suggestions, setSuggestions] = useState<PermissionSuggestion[]>([]);
+  const [newRule, setNewRule] = useState("");
+  const [behavior, setBehavior] = useState<PermissionBehavior>("allow");
+  const [scope, setScope] = useState<SettingsScope>("project");
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getPermissionRules(projectId)
+      .then(setRules)
+      .catch((err) => setError(String(err)));
+    suggestPermissionRules(projectId)
+      .then(setSuggestions)
+      .catch((err) => console.error("Failed to load rule suggestions:", err));
+  }, [projectId]);
+
+  const handleAccept = async (suggestion: PermissionSuggestion) => {
+    try {
+      setRules(await addPermissionRule(projectId, "local", suggestion.behavior, suggestion.rule));
+      setSuggestions((prev) => prev.filter((s) => s.rule !== suggestion.rule));
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleAdd = async () => {
+    if (!newRule.trim()) return;
+    try {
+      setRules(await addPermissionRule(projectId, scope, behavior, newRule));
+      setNewRule("");
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleRemove = async (rule: PermissionRule) => {
+    try {
+      setRules(await removePermissionRule(projectId, rule.scope, rule.behavior, rule.rule));
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
   return (
     <div className="space-y-4 max-w-2xl">
       {/* Permissions / Rules */}
@@ -832,28 +918,92 @@ function SettingsTab() {
 
           {/* Current Rules */}
           <div className="space-y-2">
-            {projectRules.map((rule) => (
+            {rules.map((rule) => (
               <div
-                key={rule.id}
+                key={`${rule.scope}:${rule.behavior}:${rule.rule}`}
                 className="flex items-center justify-between py-2 px-3 bg-bg-elevated"
               >
                 <div className="flex items-center gap-2">
-                  <Badge variant={rule.type === "allow" ? "success" : "error"}>
-                    {rule.type}
-                  </Badge>
+                  <Badge variant={behaviorBadges[rule.behavior]}>{rule.behavior}</Badge>
                   <code className="text-xs text-text-primary">{rule.rule}</code>
+                  <span className="text-xs text-text-muted" title={rule.source}>
+                    {rule.scope}
+                  </span>
                 </div>
-                <button className="p-1 hover:bg-bg-hover rounded transition-colors">
+                <button
+                  onClick={() => handleRemove(rule)}
+                  className="p-1 hover:bg-bg-hover rounded transition-colors"
+                >
                   <Trash2 size={12} className="text-text-muted" />
                 </button>
               </div>
             ))}
+            {rules.length === 0 && (
+              <p className="text-xs text-text-muted">No permission rules configured</p>
+            )}
           </div>
 
-          <Button variant="outline" size="sm" className="gap-1.5">
-            <Plus size={14} />
-            Add Rule
-          </Button>
+          {suggestions.length > 0 && (
+            <div className="space-y-2">
+              <div className="text-xs text-text-muted">Suggested from session history</div>
+              {suggestions.map((suggestion) => (
+                <div
+                  key={suggestion.rule}
+                  className="flex items-center justify-between py-2 px-3 border border-border-subtle"
+                >
+                  <div className="flex items-center gap-2" title={suggestion.examples.join("\n")}>
+                    <Badge variant={behaviorBadges[suggestion.behavior]}>{suggestion.behavior}</Badge>
+                    <code className="text-xs text-text-primary">{suggestion.rule}</code>
+                    <span className="text-xs text-text-muted">{suggestion.calls} calls</span>
+                    {suggestion.risk && (
+                      <span className="text-xs text-warning">{suggestion.risk}</span>
+                    )}
+                  </div>
+                  <button
+                    onClick={() => handleAccept(suggestion)}
+                    className="p-1 hover:bg-bg-hover rounded transition-colors"
+                  >
+                    <Plus size={12} className="text-text-muted" />
+                  </button>
+                </div>
+              ))}
+            </div>
+          )}
+
+          {error && <p className="text-xs text-error">{error}</p>}
+
+          <div className="flex items-center gap-2">
+            <input
+              type="text"
+              value={newRule}
+              onChange={(e) => setNewRule(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+              placeholder="Bash(npm test:*)"
+              className="flex-1 px-3 py-1.5 text-xs bg-bg-elevated border border-border-subtle rounded outline-none focus:border-accent"
+            />
+            <select
+              value={behavior}
+              onChange={(e) => setBehavior(e.target.value as PermissionBehavior)}
+              className="px-2 py-1.5 text-xs bg-bg-elevated border border-border-subtle rounded outline-none"
+            >
+              <option value="allow">allow</option>
+              <option value="ask">ask</option>
+              <option value="deny">deny</option>
+            </select>
+            <select
+              value={scope}
+              onChange={(e) => setScope(e.target.value as SettingsScope)}
+              className="px-2 py-1.5 text-xs bg-bg-elevated border border-border-subtle rounded outline-none"
+            >
+              <option value="project">project</option>
+              <option value="local">local</option>
+              <option value="user">user</option>
+            </select>
+            <Button variant="outline" size="sm" className="gap-1.5" onClick={handleAdd}>
+              <Plus size={14} />
+              Add Rule
+            </Button>
+          </div>
         </CardContent>
       </Card>
 
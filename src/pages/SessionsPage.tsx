@@ -4,6 +4,7 @@ import { Badge } from "@/components/ui/badge";
 import { Clock, MessageSquare, Zap, ArrowUpRight, Search, X, Loader2 } from "lucide-react";
 import {
   getRecentSessions,
+  onSessionUpdated,
   type ClaudeSession,
 } from "@/services/tauri";
 
@@ -65,6 +66,16 @@ export function SessionsPage() {
     fetchSessions();
   }, []);
 
+  // Keep the list live as Claude writes to session files
+  useEffect(() => {
+    const unlisten = onSessionUpdated((updated) => {
+      setSessions((prev) => [updated, ...prev.filter((s) => s.id !== updated.id)]);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const fetchSessions = async () => {
     try {
       setLoading(true);
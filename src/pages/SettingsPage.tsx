@@ -1,7 +1,8 @@
-import { useState } from "react";
+import { useState, useEffect } from "react";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
-import { FolderOpen, Bell, Zap, Info, Check } from "lucide-react";
+import { FolderOpen, Bell, Zap, Info, Check, ShieldAlert } from "lucide-react";
+import { getConfig, saveConfig, type Config } from "@/services/tauri";
 
 interface Settings {
   launchOnStartup: boolean;
@@ -22,6 +23,25 @@ const defaultSettings: Settings = {
 export function SettingsPage() {
   const [settings, setSettings] = useState<Settings>(defaultSettings);
   const [saved, setSaved] = useState(false);
+  const [config, setConfig] = useState<Config | null>(null);
+
+  useEffect(() => {
+    getConfig()
+      .then(setConfig)
+      .catch((err) => console.error("Failed to load config:", err));
+  }, []);
+
+  // Approvals apply to running sessions right away, so they save on change
+  const updateApprovals = async (enabled: boolean) => {
+    if (!config) return;
+    const next = { ...config, approvals_enabled: enabled };
+    try {
+      await saveConfig(next);
+      setConfig(next);
+    } catch (err) {
+      console.error("Failed to save config:", err);
+    }
+  };
 
   const updateSetting = <K extends keyof Settings>(key: K, value: Settings[K]) => {
     setSettings((prev) => ({ ...prev, [key]: value }));
@@ -128,6 +148,32 @@ export function SettingsPage() {
         </CardContent>
       </Card>
 
+      {/* Approvals */}
+      <Card>
+        <CardHeader className="pb-2">
+          <CardTitle className="flex items-center gap-2">
+            <ShieldAlert size={14} />
+            Approvals
+          </CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-3">
+          <div className="flex items-center justify-between">
+            <div>
+              <div className="text-xs text-text-primary">Approve tool calls in Tusker</div>
+              <div className="text-[10px] text-text-muted">
+                {config
+                  ? `Projects with Tusker hooks wait up to ${config.approval_timeout_secs}s, then ${config.approval_default}`
+                  : "Projects with Tusker hooks wait for your answer"}
+              </div>
+            </div>
+            <ToggleSwitch
+              enabled={config?.approvals_enabled ?? false}
+              onChange={updateApprovals}
+            />
+          </div>
+        </CardContent>
+      </Card>
+
       {/* Usage */}
       <Card>
         <CardHeader className="pb-2">
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
 // Types matching Rust structs
 export interface Project {
@@ -13,17 +14,40 @@ export interface Task {
   id: string;
   project_id: string;
   content: string;
-  status: "pending" | "in_progress" | "completed";
+  status: "pending" | "in_progress" | "completed" | "failed";
   created_at: string;
   updated_at: string;
 }
 
+export interface Notification {
+  id: string;
+  type: "success" | "error" | "warning" | "info";
+  title: string;
+  message: string;
+  project_id: string | null;
+  session_id: string | null;
+  message_uuid: string | null;
+  read: boolean;
+  created_at: string;
+}
+
 export interface Config {
   launch_on_startup: boolean;
   session_notifications: boolean;
   error_alerts: boolean;
   sound_enabled: boolean;
+  session_idle_minutes: number; // Quiet time before a session counts as idle or finished
   daily_token_limit: number;
+  budget_thresholds: number[]; // Percentages of the daily token limit
+  timezone?: string | null; // IANA name, system local zone when unset
+  approvals_enabled: boolean; // Ask in Tusker before Claude runs a tool
+  approval_timeout_secs: number;
+  approval_default: ApprovalChoice; // Applied when an approval times out
+  memory_file_token_limit: number; // Approximate tokens a single CLAUDE.md may use
+  memory_total_token_limit: number; // Approximate tokens all memory loaded at startup may use
+  claude_cli_path: string | null; // `claude` from PATH when unset
+  task_concurrency: number; // Tasks the runner executes at once
+  task_permission_mode: PermissionMode; // Permission mode for tasks run headless
 }
 
 // Project commands
@@ -86,20 +110,40 @@ export interface ClaudeSession {
   first_message: string;
   message_count: number;
   total_tokens: number;
+  input_tokens: number;
+  output_tokens: number;
+  cache_read_input_tokens: number;
+  cache_creation_input_tokens: number;
+  cache_hit_ratio: number;
+  error_count: number;
+  service_tier: string | null;
   model: string | null;
   started_at: string;
   last_activity: string;
 }
 
+export type ContentBlock =
+  | { type: "text"; text: string }
+  | { type: "thinking"; thinking: string }
+  | { type: "tool_use"; id: string; name: string; input: unknown }
+  | { type: "tool_result"; tool_use_id: string; content: string; is_error: boolean }
+  | { type: "image"; media_type: string | null };
+
 export interface ClaudeMessage {
   uuid: string;
   parent_uuid: string | null;
   session_id: string;
   msg_type: "user" | "assistant";
   content: string;
+  blocks: ContentBlock[];
   model: string | null;
   input_tokens: number | null;
   output_tokens: number | null;
+  cache_read_input_tokens: number | null;
+  cache_creation_input_tokens: number | null;
+  service_tier: string | null;
+  stop_reason: string | null; // "end_turn", "tool_use", ... on assistant messages
+  is_api_error: boolean;
   timestamp: string;
 }
 
@@ -108,6 +152,7 @@ export interface DailyActivity {
   message_count: number;
   session_count: number;
   tool_call_count: number;
+  source: "cache" | "sessions";
 }
 
 export interface ModelUsage {
@@ -129,11 +174,21 @@ export interface ClaudeStats {
     message_count: number;
     timestamp: string;
   } | null;
+  hour_counts: Record<string, number>;
   tokens_today: number;
   messages_today: number;
   sessions_today: number;
 }
 
+export interface SessionError {
+  message_uuid: string;
+  session_id: string;
+  kind: "api_error" | "tool_error" | "bash_exit" | "repeated_failure";
+  detail: string;
+  tool_use_id: string | null;
+  timestamp: string;
+}
+
 // Claude commands
 export async function getClaudeStats(): Promise<ClaudeStats> {
   return invoke<ClaudeStats>("get_claude_stats");
@@ -147,6 +202,678 @@ export async function getRecentSessions(limit: number): Promise<ClaudeSession[]>
   return invoke<ClaudeSession[]>("get_recent_sessions", { limit });
 }
 
-export async function getSessionMessages(sessionId: string): Promise<ClaudeMessage[]> {
-  return invoke<ClaudeMessage[]>("get_session_messages", { sessionId });
+export async function getSessionMessages(
+  sessionId: string,
+  offset?: number,
+  limit?: number
+): Promise<ClaudeMessage[]> {
+  return invoke<ClaudeMessage[]>("get_session_messages", { sessionId, offset, limit });
+}
+
+export interface SessionSearchResult {
+  session: ClaudeSession;
+  message_uuid: string;
+  snippet: string;
+  timestamp: string;
+}
+
+export async function searchSessions(
+  query: string,
+  projectId?: string,
+  dateFrom?: string,
+  dateTo?: string
+): Promise<SessionSearchResult[]> {
+  return invoke<SessionSearchResult[]>("search_sessions", {
+    query,
+    projectId,
+    dateFrom,
+    dateTo,
+  });
+}
+
+export async function getSessionErrors(sessionId: string): Promise<SessionError[]> {
+  return invoke<SessionError[]>("get_session_errors", { sessionId });
+}
+
+// Cost types
+export interface ModelPricing {
+  input: number;
+  output: number;
+  cache_read: number;
+  cache_creation: number;
+}
+
+export interface PricingTable {
+  models: Record<string, ModelPricing>;
+}
+
+export interface TokenUsage {
+  input_tokens: number;
+  output_tokens: number;
+  cache_read_input_tokens: number;
+  cache_creation_input_tokens: number;
+}
+
+export interface MessageCost extends TokenUsage {
+  uuid: string;
+  model: string | null;
+  timestamp: string;
+  cost_usd: number;
+}
+
+export interface CostSummary extends TokenUsage {
+  key: string;
+  cost_usd: number;
+}
+
+// Cost commands
+export async function getPricing(): Promise<PricingTable> {
+  return invoke<PricingTable>("get_pricing");
+}
+
+export async function getMessageCosts(sessionId: string): Promise<MessageCost[]> {
+  return invoke<MessageCost[]>("get_message_costs", { sessionId });
+}
+
+export async function getSessionCost(sessionId: string): Promise<CostSummary> {
+  return invoke<CostSummary>("get_session_cost", { sessionId });
+}
+
+export async function getProjectCosts(): Promise<CostSummary[]> {
+  return invoke<CostSummary[]>("get_project_costs");
+}
+
+export async function getDailyCosts(projectId?: string): Promise<CostSummary[]> {
+  return invoke<CostSummary[]>("get_daily_costs", { projectId });
+}
+
+// Notification commands
+export async function getNotifications(
+  notificationType?: Notification["type"]
+): Promise<Notification[]> {
+  return invoke<Notification[]>("get_notifications", { notificationType });
+}
+
+export async function markNotificationRead(id: string): Promise<void> {
+  return invoke("mark_notification_read", { id });
+}
+
+export async function markAllNotificationsRead(): Promise<void> {
+  return invoke("mark_all_notifications_read");
+}
+
+export async function deleteNotification(id: string): Promise<void> {
+  return invoke("delete_notification", { id });
+}
+
+export async function clearNotifications(): Promise<void> {
+  return invoke("clear_notifications");
+}
+
+export function onNotificationCreated(
+  handler: (notification: Notification) => void
+): Promise<UnlistenFn> {
+  return listen<Notification>("notification-created", (event) => handler(event.payload));
+}
+
+// Hook types
+export interface HookEvent {
+  id: number;
+  session_id: string | null;
+  event_name: string; // "PreToolUse", "PostToolUse", "Notification", "Stop"
+  cwd: string | null;
+  tool_name: string | null;
+  payload: unknown; // The JSON Claude Code passed to the hook
+  received_at: string;
+}
+
+// Hook commands
+export async function getHookEvents(sessionId?: string, limit?: number): Promise<HookEvent[]> {
+  return invoke<HookEvent[]>("get_hook_events", { sessionId, limit });
+}
+
+export async function installHooks(projectId: string, scope?: SettingsScope): Promise<string> {
+  return invoke<string>("install_hooks", { projectId, scope });
+}
+
+export function onHookEvent(handler: (event: HookEvent) => void): Promise<UnlistenFn> {
+  return listen<HookEvent>("hook-event", (event) => handler(event.payload));
+}
+
+// Approval types
+export type ApprovalChoice = "allow" | "deny" | "ask";
+
+export interface PendingApproval {
+  id: string;
+  session_id: string | null;
+  tool_name: string;
+  tool_input: unknown;
+  cwd: string | null;
+  requested_at: string;
+  expires_at: string;
+}
+
+export interface ApprovalDecision {
+  id: string;
+  session_id: string | null;
+  tool_name: string;
+  tool_input: unknown;
+  cwd: string | null;
+  decision: ApprovalChoice;
+  decided_by: "user" | "timeout";
+  reason: string | null;
+  requested_at: string;
+  decided_at: string;
+}
+
+// Approval commands
+export async function getPendingApprovals(): Promise<PendingApproval[]> {
+  return invoke<PendingApproval[]>("get_pending_approvals");
+}
+
+export async function resolveApproval(
+  id: string,
+  decision: ApprovalChoice,
+  reason?: string
+): Promise<void> {
+  return invoke("resolve_approval", { id, decision, reason });
+}
+
+export async function getApprovalAudit(
+  sessionId?: string,
+  limit?: number
+): Promise<ApprovalDecision[]> {
+  return invoke<ApprovalDecision[]>("get_approval_audit", { sessionId, limit });
+}
+
+export function onApprovalRequested(
+  handler: (approval: PendingApproval) => void
+): Promise<UnlistenFn> {
+  return listen<PendingApproval>("approval-requested", (event) => handler(event.payload));
+}
+
+export function onApprovalResolved(
+  handler: (decision: ApprovalDecision) => void
+): Promise<UnlistenFn> {
+  return listen<ApprovalDecision>("approval-resolved", (event) => handler(event.payload));
+}
+
+// Settings types
+export type SettingsScope = "user" | "project" | "local";
+export type PermissionBehavior = "allow" | "deny" | "ask";
+
+export interface PermissionRule {
+  rule: string; // e.g. "Bash(npm test:*)"
+  behavior: PermissionBehavior;
+  scope: SettingsScope;
+  source: string; // Path of the settings file
+}
+
+export interface PermissionSuggestion {
+  rule: string;
+  behavior: PermissionBehavior; // "allow", or "ask" for risky rules
+  calls: number;
+  examples: string[];
+  risk: string | null; // Why the rule is risky to allow
+}
+
+// Settings commands
+export async function getPermissionRules(projectId: string): Promise<PermissionRule[]> {
+  return invoke<PermissionRule[]>("get_permission_rules", { projectId });
+}
+
+export async function addPermissionRule(
+  projectId: string,
+  scope: SettingsScope,
+  behavior: PermissionBehavior,
+  rule: string
+): Promise<PermissionRule[]> {
+  return invoke<PermissionRule[]>("add_permission_rule", { projectId, scope, behavior, rule });
+}
+
+export async function removePermissionRule(
+  projectId: string,
+  scope: SettingsScope,
+  behavior: PermissionBehavior,
+  rule: string
+): Promise<PermissionRule[]> {
+  return invoke<PermissionRule[]>("remove_permission_rule", { projectId, scope, behavior, rule });
+}
+
+export async function suggestPermissionRules(projectId: string): Promise<PermissionSuggestion[]> {
+  return invoke<PermissionSuggestion[]>("suggest_permission_rules", { projectId });
+}
+
+// Memory types
+export type MemoryScope = "user" | "parent" | "project" | "local" | "nested" | "import";
+
+export interface MemoryFile {
+  path: string;
+  name: string; // Relative to the project, or ~/-relative outside it
+  scope: MemoryScope;
+  exists: boolean; // The user and project CLAUDE.md are listed before they exist
+  size: number;
+  modified: string | null;
+  imported_from: string | null;
+}
+
+export interface MemoryVersion {
+  id: number;
+  path: string;
+  source: "save" | "restore" | "external"; // "external" keeps edits made outside Tusker
+  size: number;
+  saved_at: string;
+}
+
+export interface DiffLine {
+  op: "same" | "added" | "removed";
+  text: string;
+}
+
+export interface MemoryFileSize {
+  path: string;
+  name: string;
+  scope: MemoryScope;
+  tokens: number; // Approximate, about four characters per token
+  lines: number;
+  loaded_at_start: boolean; // Nested files load only when Claude works in their directory
+}
+
+export interface LintIssue {
+  kind: "file_too_large" | "total_too_large" | "broken_import" | "duplicate_section";
+  file: string | null;
+  message: string;
+}
+
+export interface MemoryLintReport {
+  files: MemoryFileSize[];
+  total_tokens: number;
+  file_token_limit: number;
+  total_token_limit: number;
+  issues: LintIssue[];
+}
+
+// Memory commands
+export async function getMemoryFiles(projectId: string): Promise<MemoryFile[]> {
+  return invoke<MemoryFile[]>("get_memory_files", { projectId });
+}
+
+export async function readMemoryFile(projectId: string, path: string): Promise<string> {
+  return invoke<string>("read_memory_file", { projectId, path });
+}
+
+export async function saveMemoryFile(
+  projectId: string,
+  path: string,
+  content: string
+): Promise<MemoryVersion> {
+  return invoke<MemoryVersion>("save_memory_file", { projectId, path, content });
+}
+
+export async function getMemoryVersions(path: string): Promise<MemoryVersion[]> {
+  return invoke<MemoryVersion[]>("get_memory_versions", { path });
+}
+
+export async function getMemoryVersionContent(id: number): Promise<string> {
+  return invoke<string>("get_memory_version_content", { id });
+}
+
+export async function diffMemoryVersion(id: number): Promise<DiffLine[]> {
+  return invoke<DiffLine[]>("diff_memory_version", { id });
+}
+
+export async function lintProjectMemory(projectId: string): Promise<MemoryLintReport> {
+  return invoke<MemoryLintReport>("lint_project_memory", { projectId });
+}
+
+export async function restoreMemoryVersion(projectId: string, id: number): Promise<MemoryVersion> {
+  return invoke<MemoryVersion>("restore_memory_version", { projectId, id });
+}
+
+// Slash command and agent types
+export type CustomKind = "command" | "agent";
+export type CustomScope = "user" | "project";
+
+export interface CustomDefinition {
+  description: string | null;
+  allowed_tools: string[]; // "allowed-tools" for commands, "tools" for agents
+  model: string | null;
+  argument_hint: string | null; // Commands only
+  body: string;
+}
+
+export interface CustomFile {
+  kind: CustomKind;
+  scope: CustomScope;
+  name: string;
+  namespace: string | null; // Subdirectory of a command, e.g. "frontend"
+  path: string;
+  definition: CustomDefinition;
+  invocations: number;
+  last_used: string | null;
+  problems: string[];
+}
+
+// Slash command and agent commands
+export async function getCustomFiles(projectId: string): Promise<CustomFile[]> {
+  return invoke<CustomFile[]>("get_custom_files", { projectId });
+}
+
+export async function createCustomFile(
+  projectId: string,
+  kind: CustomKind,
+  scope: CustomScope,
+  name: string,
+  definition: CustomDefinition,
+  namespace?: string
+): Promise<CustomFile[]> {
+  return invoke<CustomFile[]>("create_custom_file", {
+    projectId,
+    kind,
+    scope,
+    name,
+    namespace,
+    definition,
+  });
+}
+
+export async function updateCustomFile(
+  projectId: string,
+  path: string,
+  definition: CustomDefinition
+): Promise<CustomFile[]> {
+  return invoke<CustomFile[]>("update_custom_file", { projectId, path, definition });
+}
+
+export async function renameCustomFile(
+  projectId: string,
+  path: string,
+  newName: string
+): Promise<CustomFile[]> {
+  return invoke<CustomFile[]>("rename_custom_file", { projectId, path, newName });
+}
+
+export async function deleteCustomFile(projectId: string, path: string): Promise<CustomFile[]> {
+  return invoke<CustomFile[]>("delete_custom_file", { projectId, path });
+}
+
+// MCP server types
+export type McpScope = "user" | "project" | "local";
+export type McpTransport = "stdio" | "sse" | "http";
+
+export interface McpServerConfig {
+  transport: McpTransport;
+  command: string | null; // stdio only
+  args: string[];
+  env: Record<string, string>; // Values are masked unless they are ${VAR} references
+  url: string | null; // sse and http only
+  headers: Record<string, string>; // Masked like env
+}
+
+export interface McpServer {
+  name: string;
+  scope: McpScope;
+  source: string;
+  config: McpServerConfig;
+  enabled: boolean;
+  problems: string[];
+}
+
+// MCP server commands
+export async function getMcpServers(projectId: string): Promise<McpServer[]> {
+  return invoke<McpServer[]>("get_mcp_servers", { projectId });
+}
+
+export async function addMcpServer(
+  projectId: string,
+  scope: McpScope,
+  name: string,
+  config: McpServerConfig
+): Promise<McpServer[]> {
+  return invoke<McpServer[]>("add_mcp_server", { projectId, scope, name, config });
+}
+
+export async function updateMcpServer(
+  projectId: string,
+  scope: McpScope,
+  name: string,
+  config: McpServerConfig
+): Promise<McpServer[]> {
+  return invoke<McpServer[]>("update_mcp_server", { projectId, scope, name, config });
+}
+
+export async function removeMcpServer(
+  projectId: string,
+  scope: McpScope,
+  name: string
+): Promise<McpServer[]> {
+  return invoke<McpServer[]>("remove_mcp_server", { projectId, scope, name });
+}
+
+export async function setMcpServerEnabled(
+  projectId: string,
+  scope: McpScope,
+  name: string,
+  enabled: boolean
+): Promise<McpServer[]> {
+  return invoke<McpServer[]>("set_mcp_server_enabled", { projectId, scope, name, enabled });
+}
+
+// Launch types
+export type PermissionMode = "default" | "acceptEdits" | "plan" | "bypassPermissions";
+
+export interface LaunchOptions {
+  prompt?: string | null;
+  resume?: string | null; // Session id to resume
+  model?: string | null;
+  permission_mode?: PermissionMode | null;
+}
+
+export interface ClaudeProcess {
+  id: string;
+  project_id: string;
+  cwd: string;
+  pid: number;
+  args: string[];
+  status: "running" | "exited" | "stopped";
+  exit_code: number | null;
+  started_at: string;
+  ended_at: string | null;
+}
+
+export interface ProcessOutput {
+  process_id: string;
+  stream: "stdout" | "stderr";
+  line: string;
+}
+
+// Launch commands
+export async function launchClaude(projectId: string, options: LaunchOptions): Promise<ClaudeProcess> {
+  return invoke<ClaudeProcess>("launch_claude", { projectId, options });
+}
+
+export async function stopClaude(processId: string): Promise<void> {
+  return invoke("stop_claude", { processId });
+}
+
+export async function getClaudeProcesses(): Promise<ClaudeProcess[]> {
+  return invoke<ClaudeProcess[]>("get_claude_processes");
+}
+
+export function onClaudeOutput(handler: (output: ProcessOutput) => void): Promise<UnlistenFn> {
+  return listen<ProcessOutput>("claude-output", (event) => handler(event.payload));
+}
+
+export function onClaudeExited(handler: (process: ClaudeProcess) => void): Promise<UnlistenFn> {
+  return listen<ClaudeProcess>("claude-exited", (event) => handler(event.payload));
+}
+
+// Task runner types
+export interface TaskRun {
+  id: string;
+  task_id: string;
+  project_id: string;
+  status: "queued" | "running" | "completed" | "failed" | "cancelled";
+  process_id: string | null; // Matches ProcessOutput.process_id while running
+  session_id: string | null;
+  result: string | null;
+  error: string | null;
+  cost_usd: number | null;
+  duration_ms: number | null;
+  num_turns: number | null;
+  queued_at: string;
+  started_at: string | null;
+  ended_at: string | null;
+}
+
+// Task runner commands
+export async function runTask(taskId: string): Promise<TaskRun[]> {
+  return invoke<TaskRun[]>("run_task", { taskId });
+}
+
+export async function runProjectTasks(projectId: string): Promise<TaskRun[]> {
+  return invoke<TaskRun[]>("run_project_tasks", { projectId });
+}
+
+export async function cancelTaskRun(runId: string): Promise<void> {
+  return invoke("cancel_task_run", { runId });
+}
+
+export async function getTaskRuns(projectId?: string, taskId?: string): Promise<TaskRun[]> {
+  return invoke<TaskRun[]>("get_task_runs", { projectId, taskId });
+}
+
+export function onTaskRunUpdated(handler: (run: TaskRun) => void): Promise<UnlistenFn> {
+  return listen<TaskRun>("task-run-updated", (event) => handler(event.payload));
+}
+
+// Task session types
+export interface LinkedSession {
+  session_id: string;
+  source: "manual" | "run" | "todo"; // Linked by hand, by the task runner or by a todo import
+  linked_at: string;
+  session: ClaudeSession | null; // null until the session is indexed
+  cost_usd: number;
+}
+
+export interface SessionSuggestion {
+  session: ClaudeSession;
+  score: number; // Share of the task's words found in the first message
+}
+
+export interface TaskUsage extends TokenUsage {
+  task_id: string;
+  session_count: number;
+  total_tokens: number;
+  cost_usd: number;
+  time_spent_secs: number;
+}
+
+// Task session commands
+export async function getTaskSessions(taskId: string): Promise<LinkedSession[]> {
+  return invoke<LinkedSession[]>("get_task_sessions", { taskId });
+}
+
+export async function attachTaskSession(taskId: string, sessionId: string): Promise<LinkedSession[]> {
+  return invoke<LinkedSession[]>("attach_task_session", { taskId, sessionId });
+}
+
+export async function detachTaskSession(taskId: string, sessionId: string): Promise<LinkedSession[]> {
+  return invoke<LinkedSession[]>("detach_task_session", { taskId, sessionId });
+}
+
+export async function suggestTaskSessions(taskId: string): Promise<SessionSuggestion[]> {
+  return invoke<SessionSuggestion[]>("suggest_task_sessions", { taskId });
+}
+
+export async function getTaskUsage(projectId?: string): Promise<TaskUsage[]> {
+  return invoke<TaskUsage[]>("get_task_usage", { projectId });
+}
+
+// Todo import types
+export interface TodoItem {
+  content: string;
+  status: "pending" | "in_progress" | "completed";
+  active_form: string | null; // Shown while in progress, e.g. "Running tests"
+  imported: boolean; // A task with the same content exists in the project
+}
+
+export interface SessionTodos {
+  session_id: string;
+  first_message: string;
+  source: "tool_call" | "todo_file";
+  updated_at: string;
+  items: TodoItem[];
+}
+
+// Todo import commands
+export async function getSessionTodos(projectId: string): Promise<SessionTodos[]> {
+  return invoke<SessionTodos[]>("get_session_todos", { projectId });
+}
+
+export async function importTodos(
+  projectId: string,
+  sessionId: string,
+  items: TodoItem[]
+): Promise<Task[]> {
+  return invoke<Task[]>("import_todos", { projectId, sessionId, items });
+}
+
+// Budget types
+export interface BudgetStatus {
+  date: string;
+  daily_token_limit: number;
+  tokens_used: number;
+  percent_used: number;
+  projected_tokens: number; // End-of-day usage if the current rate continues
+  projected_percent: number;
+  thresholds: number[];
+  crossed_thresholds: number[];
+}
+
+export interface BudgetAlert {
+  threshold: number;
+  status: BudgetStatus;
+}
+
+// Budget commands
+export async function getBudgetStatus(): Promise<BudgetStatus> {
+  return invoke<BudgetStatus>("get_budget_status");
+}
+
+// Live session events
+export interface SessionLifecycleEvent {
+  session: ClaudeSession;
+  state: "started" | "idle" | "finished";
+  sound: boolean;
+}
+
+export function onSessionStarted(
+  handler: (session: ClaudeSession) => void
+): Promise<UnlistenFn> {
+  return listen<ClaudeSession>("session-started", (event) => handler(event.payload));
+}
+
+export function onSessionMessage(
+  handler: (message: ClaudeMessage) => void
+): Promise<UnlistenFn> {
+  return listen<ClaudeMessage>("session-message", (event) => handler(event.payload));
+}
+
+export function onSessionUpdated(
+  handler: (session: ClaudeSession) => void
+): Promise<UnlistenFn> {
+  return listen<ClaudeSession>("session-updated", (event) => handler(event.payload));
+}
+
+export function onBudgetThreshold(
+  handler: (alert: BudgetAlert) => void
+): Promise<UnlistenFn> {
+  return listen<BudgetAlert>("budget-threshold", (event) => handler(event.payload));
+}
+
+export function onSessionLifecycle(
+  handler: (event: SessionLifecycleEvent) => void
+): Promise<UnlistenFn> {
+  return listen<SessionLifecycleEvent>("session-lifecycle", (event) => handler(event.payload));
 }